@@ -1,4 +1,6 @@
-use aoc2018::read_input;
+//! Day 15 with a breadth-first search back from the chosen target square to
+//! pick the first step
+
 use std::collections::{VecDeque, HashMap, HashSet};
 
 #[derive(Clone, Copy, Eq, PartialEq, Debug)]
@@ -262,11 +264,11 @@ impl State {
     }
 
     fn outcome(&self) -> i32 {
-        self.units.iter().map(|u| u.hp).sum()
+        self.units.iter().filter(|u| u.hp > 0).map(|u| u.hp).sum()
     }
 }
 
-fn part1(input: &str) -> i32 {
+pub fn part1(input: &str) -> i32 {
     let mut state = State::from_input(input, 3);
     let mut rounds = 0;
 
@@ -280,7 +282,7 @@ fn part1(input: &str) -> i32 {
     rounds * state.outcome()
 }
 
-fn part2(input: &str) -> i32 {
+pub fn part2(input: &str) -> i32 {
     for elf_attack in 4..=200 {
         let mut state = State::from_input(input, elf_attack);
         let initial_elf_count = state.units.iter().filter(|u| u.unit_type == UnitType::Elf).count();
@@ -308,13 +310,6 @@ fn part2(input: &str) -> i32 {
     0
 }
 
-fn main() {
-    let input = read_input(15);
-
-    println!("Part 1: {}", part1(&input));
-    println!("Part 2: {}", part2(&input));
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
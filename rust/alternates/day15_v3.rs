@@ -1,4 +1,6 @@
-use aoc2018::read_input;
+//! Day 15 with a breadth-first search from each open neighbour of the unit to
+//! pick the first step
+
 use std::collections::{VecDeque, HashMap, HashSet};
 
 #[derive(Clone, Copy, Eq, PartialEq, Debug)]
@@ -155,7 +157,7 @@ impl State {
         // Now BFS from all neighbors of `from` to find which one gets to target first
         // and in case of tie, pick the one in reading order
         let neighbors = self.neighbors(from);
-        let mut best_neighbor = None;
+        let mut best_neighbor: Option<(usize, usize)> = None;
         let mut best_dist = None;
 
         for neighbor in neighbors {
@@ -273,11 +275,11 @@ impl State {
     }
 
     fn outcome(&self) -> i32 {
-        self.units.iter().map(|u| u.hp).sum()
+        self.units.iter().filter(|u| u.hp > 0).map(|u| u.hp).sum()
     }
 }
 
-fn part1(input: &str) -> i32 {
+pub fn part1(input: &str) -> i32 {
     let mut state = State::from_input(input, 3);
     let mut rounds = 0;
 
@@ -291,7 +293,7 @@ fn part1(input: &str) -> i32 {
     rounds * state.outcome()
 }
 
-fn part2(input: &str) -> i32 {
+pub fn part2(input: &str) -> i32 {
     for elf_attack in 4..=200 {
         let mut state = State::from_input(input, elf_attack);
         let initial_elf_count = state.units.iter().filter(|u| u.unit_type == UnitType::Elf).count();
@@ -319,13 +321,6 @@ fn part2(input: &str) -> i32 {
     0
 }
 
-fn main() {
-    let input = read_input(15);
-
-    println!("Part 1: {}", part1(&input));
-    println!("Part 2: {}", part2(&input));
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
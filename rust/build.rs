@@ -0,0 +1,60 @@
+//! Generates the day registry from the `src/dayNN.rs` files
+//!
+//! Every `src/dayNN.rs` that defines `pub struct DayNN` becomes a `pub mod dayNN`
+//! of the library and an entry in `DAYS`. Files that look like days but don't
+//! fit that pattern are reported as warnings; `aoc list` shows the days with no
+//! solution yet.
+
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Day number of a `dayNN.rs` file name
+fn day_number(file_name: &str) -> Option<u8> {
+    let digits = file_name.strip_prefix("day")?.strip_suffix(".rs")?;
+    if digits.len() != 2 || !digits.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+    let day = digits.parse().ok()?;
+    (1..=25).contains(&day).then_some(day)
+}
+
+fn main() {
+    let manifest_dir = PathBuf::from(env::var("CARGO_MANIFEST_DIR").unwrap());
+    let src = manifest_dir.join("src");
+    println!("cargo:rerun-if-changed=src");
+
+    let mut days: Vec<(u8, PathBuf)> = Vec::new();
+    for entry in fs::read_dir(&src).expect("Failed to read src/") {
+        let path = entry.expect("Failed to read src/ entry").path();
+        let file_name = path.file_name().unwrap().to_string_lossy().into_owned();
+        if !file_name.starts_with("day") {
+            continue;
+        }
+
+        let Some(day) = day_number(&file_name) else {
+            println!("cargo:warning=src/{} is not named dayNN.rs and is not compiled", file_name);
+            continue;
+        };
+        let source = fs::read_to_string(&path).expect("Failed to read day file");
+        if !source.contains(&format!("pub struct Day{:02}", day)) {
+            println!("cargo:warning=src/{} has no `pub struct Day{:02}` and is not registered", file_name, day);
+            continue;
+        }
+        days.push((day, path));
+    }
+    days.sort();
+
+    let mut code = String::new();
+    for (day, path) in &days {
+        code.push_str(&format!("#[path = {:?}]\npub mod day{:02};\n", path.display().to_string(), day));
+    }
+    code.push_str("\n/// Registry of all solved days, in order\npub static DAYS: &[Day] = &[\n");
+    for (day, _) in &days {
+        code.push_str(&format!("    day!({}, day{:02}::Day{:02}),\n", day, day, day));
+    }
+    code.push_str("];\n");
+
+    let out = Path::new(&env::var("OUT_DIR").unwrap()).join("days.rs");
+    fs::write(out, code).expect("Failed to write days.rs");
+}
@@ -0,0 +1,184 @@
+//! A global allocator that counts allocations, for finding the days that
+//! allocate heavily
+//!
+//! The `aoc` runner installs it when built with the `alloc-stats` feature.
+//! Counts are kept per thread, so days measured in parallel don't mix.
+
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::cell::Cell;
+use std::fmt;
+
+/// Wraps the system allocator, counting on the allocating thread
+pub struct CountingAllocator;
+
+#[derive(Clone, Copy)]
+struct Counters {
+    allocs: u64,
+    bytes: u64,
+    live: u64,
+    peak: u64,
+}
+
+thread_local! {
+    // Const-initialized without a destructor, so using it never allocates
+    static COUNTERS: Cell<Counters> = const {
+        Cell::new(Counters {
+            allocs: 0,
+            bytes: 0,
+            live: 0,
+            peak: 0,
+        })
+    };
+}
+
+fn update(f: impl FnOnce(&mut Counters)) {
+    let _ = COUNTERS.try_with(|counters| {
+        let mut current = counters.get();
+        f(&mut current);
+        counters.set(current);
+    });
+}
+
+fn allocated(size: usize) {
+    update(|c| {
+        c.allocs += 1;
+        c.bytes += size as u64;
+        c.live += size as u64;
+        c.peak = c.peak.max(c.live);
+    });
+}
+
+fn freed(size: usize) {
+    // Memory freed on another thread than it was allocated on can't go below zero
+    update(|c| c.live = c.live.saturating_sub(size as u64));
+}
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let ptr = System.alloc(layout);
+        if !ptr.is_null() {
+            allocated(layout.size());
+        }
+        ptr
+    }
+
+    unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8 {
+        let ptr = System.alloc_zeroed(layout);
+        if !ptr.is_null() {
+            allocated(layout.size());
+        }
+        ptr
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout);
+        freed(layout.size());
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        let new = System.realloc(ptr, layout, new_size);
+        if !new.is_null() {
+            freed(layout.size());
+            allocated(new_size);
+        }
+        new
+    }
+}
+
+/// Allocations made while running some code
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct AllocStats {
+    pub allocs: u64,
+    pub bytes: u64,
+    /// Most bytes live at once, above what was live when measuring started
+    pub peak: u64,
+}
+
+/// Run `f`, counting what it allocates on this thread
+///
+/// The counts are all zero unless `CountingAllocator` is the global allocator.
+pub fn measure<T>(f: impl FnOnce() -> T) -> (T, AllocStats) {
+    let mut start = COUNTERS.with(Cell::get);
+    start.peak = start.live;
+    COUNTERS.with(|counters| counters.set(start));
+
+    let value = f();
+
+    let end = COUNTERS.with(Cell::get);
+    let stats = AllocStats {
+        allocs: end.allocs - start.allocs,
+        bytes: end.bytes - start.bytes,
+        peak: end.peak.saturating_sub(start.live),
+    };
+    (value, stats)
+}
+
+/// `bytes` with a binary unit, e.g. `1.5 KiB`
+pub fn format_bytes(bytes: u64) -> String {
+    const UNITS: [&str; 4] = ["KiB", "MiB", "GiB", "TiB"];
+    if bytes < 1024 {
+        return format!("{} B", bytes);
+    }
+    let mut value = bytes as f64 / 1024.0;
+    let mut unit = 0;
+    while value >= 1024.0 && unit < UNITS.len() - 1 {
+        value /= 1024.0;
+        unit += 1;
+    }
+    format!("{:.1} {}", value, UNITS[unit])
+}
+
+impl fmt::Display for AllocStats {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{} allocs, {} allocated, peak {}",
+            self.allocs,
+            format_bytes(self.bytes),
+            format_bytes(self.peak)
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[global_allocator]
+    static ALLOCATOR: CountingAllocator = CountingAllocator;
+
+    #[test]
+    fn test_measure_counts_allocations() {
+        let (len, stats) = measure(|| {
+            let mut total = 0;
+            for _ in 0..10 {
+                total += vec![0u8; 1000].len();
+            }
+            total
+        });
+        assert_eq!(len, 10_000);
+        assert_eq!(stats.allocs, 10);
+        assert_eq!(stats.bytes, 10_000);
+        assert_eq!(stats.peak, 1000);
+    }
+
+    #[test]
+    fn test_peak_tracks_live_bytes() {
+        let (kept, stats) = measure(|| {
+            let kept = vec![0u64; 512];
+            drop(vec![0u8; 100]);
+            kept
+        });
+        assert_eq!(stats.allocs, 2);
+        assert_eq!(stats.peak, 4096 + 100);
+        assert_eq!(kept.len(), 512);
+        assert_eq!(measure(|| 1 + 1).1, AllocStats::default());
+    }
+
+    #[test]
+    fn test_format_bytes() {
+        assert_eq!(format_bytes(512), "512 B");
+        assert_eq!(format_bytes(1536), "1.5 KiB");
+        assert_eq!(format_bytes(3 * 1024 * 1024), "3.0 MiB");
+    }
+}
@@ -0,0 +1,188 @@
+use crate::{AocError, Answer};
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::PathBuf;
+
+/// Answers accepted by Advent of Code for one day, stored in `answers/YYYY/dayNN.txt`
+///
+/// The file has one `partN: <answer>` line per part. A multi-line answer is
+/// written as an empty `partN:` line followed by its rows.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct KnownAnswers {
+    pub part1: Option<String>,
+    pub part2: Option<String>,
+}
+
+/// Outcome of checking one answer against the known-answer registry
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Verdict {
+    Pass,
+    Fail { expected: String },
+    Missing,
+}
+
+impl KnownAnswers {
+    pub fn parse(text: &str) -> Self {
+        let mut answers = KnownAnswers::default();
+        let mut current: Option<(u8, Vec<&str>)> = None;
+
+        for line in text.lines() {
+            let header = line
+                .strip_prefix("part1:")
+                .map(|rest| (1, rest))
+                .or_else(|| line.strip_prefix("part2:").map(|rest| (2, rest)));
+
+            match header {
+                Some((part, rest)) => {
+                    if let Some((part, rows)) = current.take() {
+                        answers.set(part, &rows.join("\n"));
+                    }
+                    let rest = rest.trim();
+                    if rest.is_empty() {
+                        current = Some((part, Vec::new()));
+                    } else {
+                        answers.set(part, rest);
+                    }
+                }
+                None => {
+                    if let Some((_, rows)) = current.as_mut() {
+                        rows.push(line.trim_end());
+                    }
+                }
+            }
+        }
+
+        if let Some((part, rows)) = current {
+            answers.set(part, &rows.join("\n"));
+        }
+        answers
+    }
+
+    pub fn get(&self, part: u8) -> Option<&str> {
+        match part {
+            1 => self.part1.as_deref(),
+            2 => self.part2.as_deref(),
+            _ => None,
+        }
+    }
+
+    pub fn set(&mut self, part: u8, answer: &str) {
+        let answer = answer.trim_matches('\n');
+        let value = (!answer.is_empty()).then(|| answer.to_string());
+        match part {
+            1 => self.part1 = value,
+            2 => self.part2 = value,
+            _ => panic!("Invalid part: {}", part),
+        }
+    }
+
+    /// Compare a computed answer with the recorded one for `part`
+    pub fn verify(&self, part: u8, answer: &Answer) -> Verdict {
+        match self.get(part) {
+            None => Verdict::Missing,
+            Some(expected) if expected == answer.to_string() => Verdict::Pass,
+            Some(expected) => Verdict::Fail {
+                expected: expected.to_string(),
+            },
+        }
+    }
+}
+
+impl fmt::Display for KnownAnswers {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for part in 1..=2 {
+            match self.get(part) {
+                Some(answer) if answer.contains('\n') => writeln!(f, "part{}:\n{}", part, answer)?,
+                Some(answer) => writeln!(f, "part{}: {}", part, answer)?,
+                None => {}
+            }
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for Verdict {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Verdict::Pass => write!(f, "PASS"),
+            Verdict::Fail { expected } if expected.contains('\n') => {
+                write!(f, "FAIL (expected:\n{})", expected)
+            }
+            Verdict::Fail { expected } => write!(f, "FAIL (expected {})", expected),
+            Verdict::Missing => write!(f, "MISSING"),
+        }
+    }
+}
+
+fn answers_path(year: u16, day: u8) -> PathBuf {
+    PathBuf::from(format!("answers/{}/day{:02}.txt", year, day))
+}
+
+/// Load the recorded answers for a day; a missing file means no answers yet
+pub fn load_answers(year: u16, day: u8) -> Result<KnownAnswers, AocError> {
+    let path = answers_path(year, day);
+    match fs::read_to_string(&path) {
+        Ok(text) => Ok(KnownAnswers::parse(&text)),
+        Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(KnownAnswers::default()),
+        Err(e) => Err(AocError::io(path, e)),
+    }
+}
+
+/// Record an accepted answer for one part, keeping the other part as is
+pub fn record_answer(year: u16, day: u8, part: u8, answer: &str) -> Result<(), AocError> {
+    let mut answers = load_answers(year, day)?;
+    answers.set(part, answer);
+
+    let path = answers_path(year, day);
+    let dir = path.parent().unwrap();
+    fs::create_dir_all(dir).map_err(|e| AocError::io(dir, e))?;
+    fs::write(&path, answers.to_string()).map_err(|e| AocError::io(path, e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_single_line_answers() {
+        let answers = KnownAnswers::parse("part1: 439\npart2: CABDFE\n");
+        assert_eq!(answers.get(1), Some("439"));
+        assert_eq!(answers.get(2), Some("CABDFE"));
+    }
+
+    #[test]
+    fn test_parse_multiline_answer() {
+        let answers = KnownAnswers::parse("part1:\n#...#\n#####\n#...#\n\npart2: 3\n");
+        assert_eq!(answers.get(1), Some("#...#\n#####\n#...#"));
+        assert_eq!(answers.get(2), Some("3"));
+    }
+
+    #[test]
+    fn test_parse_missing_part() {
+        let answers = KnownAnswers::parse("part1: 17\n");
+        assert_eq!(answers.get(2), None);
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let mut answers = KnownAnswers::default();
+        answers.set(1, "#.#\n.#.");
+        answers.set(2, "10813");
+        assert_eq!(KnownAnswers::parse(&answers.to_string()), answers);
+    }
+
+    #[test]
+    fn test_verify() {
+        let answers = KnownAnswers::parse("part1: 42\npart2:\n#.\n.#\n");
+        assert_eq!(answers.verify(1, &Answer::Integer(42)), Verdict::Pass);
+        assert_eq!(
+            answers.verify(1, &Answer::Integer(41)),
+            Verdict::Fail {
+                expected: "42".to_string()
+            }
+        );
+        assert_eq!(answers.verify(2, &Answer::grid("#.\n.#\n")), Verdict::Pass);
+        assert_eq!(KnownAnswers::default().verify(1, &Answer::Integer(1)), Verdict::Missing);
+    }
+}
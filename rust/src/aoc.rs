@@ -0,0 +1,320 @@
+use aoc2018::alloc::measure;
+use aoc2018::answers::{load_answers, Verdict};
+use aoc2018::args::InputSource;
+use aoc2018::pool::for_each_ordered;
+use aoc2018::report::{summary_table, Format, Record, SLOWEST, TSV_HEADER};
+use aoc2018::bench::{append_csv, bench_day, format_duration, DayBench, STAGES};
+use aoc2018::{find_day, AocError, Day, DAYS};
+use std::env;
+use std::io::IsTerminal;
+use std::panic::{self, AssertUnwindSafe};
+use std::path::Path;
+use std::time::Instant;
+
+#[cfg(feature = "alloc-stats")]
+#[global_allocator]
+static ALLOCATOR: aoc2018::alloc::CountingAllocator = aoc2018::alloc::CountingAllocator;
+
+fn usage() -> ! {
+    eprintln!("Usage: cargo run --bin aoc run <day|all> [--part <1|2>] [--verify] [--format text|json|tsv] [--jobs N]");
+    eprintln!("           [--alloc]");
+    eprintln!("           [--input FILE|-] [--example N]");
+    eprintln!("       cargo run --bin aoc bench <day|all> [--warmup N] [--iterations N] [--output FILE] [--input FILE|-] [--example N]");
+    eprintln!("       cargo run --bin aoc list");
+    eprintln!("Example: cargo run --release --bin aoc run 7 --part 2");
+    eprintln!();
+    eprintln!("  --input       read the input from FILE, or from stdin for -");
+    eprintln!("  --example     use example N from the puzzle description");
+    eprintln!("  --verify      compare answers with the accepted ones in answers/2018/dayNN.txt");
+    eprintln!("  --format      print a JSON object or TSV line per part instead of text");
+    eprintln!("  --jobs        days run in parallel (default 1)");
+    eprintln!("  --alloc       count allocations per part (needs --features alloc-stats)");
+    eprintln!("  --warmup      untimed runs before measuring (default 1)");
+    eprintln!("  --iterations  timed runs per day (default 10)");
+    eprintln!("  --output      CSV file the results are appended to (default bench.csv)");
+    std::process::exit(1);
+}
+
+/// Verification counts across all runs
+#[derive(Default)]
+struct Tally {
+    passed: usize,
+    failed: usize,
+    missing: usize,
+}
+
+impl Tally {
+    fn add(&mut self, verdict: &Verdict) {
+        match verdict {
+            Verdict::Pass => self.passed += 1,
+            Verdict::Fail { .. } => self.failed += 1,
+            Verdict::Missing => self.missing += 1,
+        }
+    }
+}
+
+/// Run `parts` of `day`, verifying the answers and counting allocations if asked to
+fn run_day(
+    day: &Day,
+    source: &InputSource,
+    parts: &[u8],
+    verify: bool,
+    count_allocs: bool,
+) -> Result<Vec<Record>, String> {
+    let error = |e: AocError| e.to_string();
+    let known = if verify { Some(load_answers(aoc2018::YEAR, day.number).map_err(error)?) } else { None };
+    let input = source.read(day).map_err(error)?;
+    let parsed = day.solution.parse(&input).map_err(error)?;
+
+    let mut records = Vec::new();
+    for &part in parts {
+        let start = Instant::now();
+        let (answer, alloc) = if count_allocs {
+            let (answer, stats) = measure(|| day.solution.part(part, parsed.as_ref()));
+            (answer, Some(stats))
+        } else {
+            (day.solution.part(part, parsed.as_ref()), None)
+        };
+        let elapsed = start.elapsed();
+        records.push(Record {
+            day: day.number,
+            part,
+            verdict: known.as_ref().map(|known| known.verify(part, &answer)),
+            answer,
+            elapsed,
+            alloc,
+        });
+    }
+    Ok(records)
+}
+
+/// Days selected by a `<day|all>` argument
+fn select_days(arg: &str, source: &InputSource) -> Vec<&'static Day> {
+    if arg == "all" {
+        if source.is_single() {
+            eprintln!("--input gives the input for a single day, not all");
+            std::process::exit(1);
+        }
+        return DAYS.iter().collect();
+    }
+    let number: u8 = arg.parse().unwrap_or_else(|_| {
+        eprintln!("Invalid day number: {}", arg);
+        std::process::exit(1);
+    });
+    match find_day(number) {
+        Some(day) => vec![day],
+        None => {
+            eprintln!("Day {} has no registered solution", number);
+            std::process::exit(1);
+        }
+    }
+}
+
+fn print_bench(result: &DayBench) {
+    for (stage, stats) in STAGES.iter().zip(&result.stages) {
+        println!(
+            "Day {:02} {:<5}  min {:>10}  median {:>10}  max {:>10}",
+            result.day,
+            stage,
+            format_duration(stats.min),
+            format_duration(stats.median),
+            format_duration(stats.max)
+        );
+    }
+}
+
+/// Split the input source out of `args`, exiting on invalid options
+fn input_source(args: &[String]) -> (InputSource, Vec<String>) {
+    InputSource::split_args(args).unwrap_or_else(|e| {
+        eprintln!("{}", e);
+        usage();
+    })
+}
+
+fn bench(args: &[String]) {
+    let (source, args) = input_source(args);
+    if args.is_empty() {
+        usage();
+    }
+    let mut warmup = 1;
+    let mut iterations = 10;
+    let mut output = String::from("bench.csv");
+    let mut rest = args[1..].iter();
+    while let Some(arg) = rest.next() {
+        let mut count = || -> usize { rest.next().and_then(|n| n.parse().ok()).unwrap_or_else(|| usage()) };
+        match arg.as_str() {
+            "--warmup" => warmup = count(),
+            "--iterations" => iterations = count().max(1),
+            "--output" => output = rest.next().cloned().unwrap_or_else(|| usage()),
+            other => {
+                eprintln!("Unknown argument: {}", other);
+                usage();
+            }
+        }
+    }
+
+    let mut results = Vec::new();
+    let mut errors = 0;
+    for day in select_days(&args[0], &source) {
+        match source.read(day).and_then(|input| bench_day(day, &input, warmup, iterations)) {
+            Ok(result) => {
+                print_bench(&result);
+                results.push(result);
+            }
+            Err(e) => {
+                eprintln!("Day {:02}: Error: {}", day.number, e);
+                errors += 1;
+            }
+        }
+    }
+
+    if !results.is_empty() {
+        if let Err(e) = append_csv(Path::new(&output), &results) {
+            eprintln!("Error: {}", e);
+            errors += 1;
+        } else {
+            println!();
+            println!("Results appended to {}", output);
+        }
+    }
+
+    if errors > 0 {
+        std::process::exit(1);
+    }
+}
+
+/// Print the days with a registered solution and the ones still missing
+fn list() {
+    let solved: Vec<String> = DAYS.iter().map(|day| day.number.to_string()).collect();
+    let missing: Vec<String> = (1..=25).filter(|&n| find_day(n).is_none()).map(|n| n.to_string()).collect();
+    println!("Solved: {}", solved.join(", "));
+    if !missing.is_empty() {
+        println!("No solution yet: {}", missing.join(", "));
+    }
+}
+
+fn main() {
+    let args: Vec<String> = env::args().skip(1).collect();
+
+    if args.len() == 1 && args[0] == "list" {
+        return list();
+    }
+    if args.len() < 2 {
+        usage();
+    }
+    match args[0].as_str() {
+        "run" => {}
+        "bench" => return bench(&args[1..]),
+        _ => usage(),
+    }
+    let (source, args) = input_source(&args);
+    if args.len() < 2 {
+        usage();
+    }
+
+    let mut parts = vec![1, 2];
+    let mut verify = false;
+    let mut format = Format::Text;
+    let mut jobs = 1;
+    let mut count_allocs = false;
+    let mut rest = args[2..].iter();
+    while let Some(arg) = rest.next() {
+        match arg.as_str() {
+            "--part" => {
+                let part: u8 = rest.next().and_then(|p| p.parse().ok()).unwrap_or_else(|| usage());
+                if part != 1 && part != 2 {
+                    eprintln!("Part must be 1 or 2");
+                    std::process::exit(1);
+                }
+                parts = vec![part];
+            }
+            "--verify" => verify = true,
+            "--alloc" => count_allocs = true,
+            "--jobs" => {
+                jobs = rest.next().and_then(|n| n.parse().ok()).filter(|&n| n > 0).unwrap_or_else(|| usage());
+            }
+            "--format" => {
+                let name = rest.next().unwrap_or_else(|| usage());
+                format = Format::parse(name).unwrap_or_else(|e| {
+                    eprintln!("{}", e);
+                    std::process::exit(1);
+                });
+            }
+            other => {
+                eprintln!("Unknown argument: {}", other);
+                usage();
+            }
+        }
+    }
+
+    if count_allocs && !cfg!(feature = "alloc-stats") {
+        eprintln!("--alloc needs the counting allocator: build with --features alloc-stats");
+        std::process::exit(1);
+    }
+    if verify && !source.is_default() {
+        eprintln!("--verify compares with the answers for the puzzle input, so it can't be used with --input or --example");
+        std::process::exit(1);
+    }
+    let days = select_days(&args[1], &source);
+
+    if format == Format::Tsv {
+        println!("{}", TSV_HEADER);
+    }
+
+    let mut tally = Tally::default();
+    let mut errors = 0;
+    let mut all_records = Vec::new();
+    for_each_ordered(
+        &days,
+        jobs,
+        |day| {
+            // A panicking day is reported like an error instead of stopping the other days
+            panic::catch_unwind(AssertUnwindSafe(|| run_day(day, &source, &parts, verify, count_allocs)))
+                .unwrap_or_else(|_| Err("the solution panicked".to_string()))
+        },
+        |day, result| {
+            if format == Format::Text && days.len() > 1 {
+                println!("Day {:02}", day.number);
+            }
+            match result {
+                Ok(records) => {
+                    for record in records {
+                        if let Some(verdict) = &record.verdict {
+                            tally.add(verdict);
+                        }
+                        println!("{}", record.format(format));
+                        all_records.push(record);
+                    }
+                }
+                Err(e) => {
+                    eprintln!("Day {:02}: Error: {}", day.number, e);
+                    errors += 1;
+                }
+            }
+        },
+    );
+
+    if format == Format::Text && days.len() > 1 {
+        println!();
+        print!("{}", summary_table(&all_records, std::io::stdout().is_terminal()));
+        println!("◀ the {} slowest days by total time of their parts", SLOWEST);
+    }
+
+    if verify {
+        // Keep stdout to records for the machine-readable formats
+        let summary = format!(
+            "Verified: {} passed, {} failed, {} missing",
+            tally.passed, tally.failed, tally.missing
+        );
+        if format == Format::Text {
+            println!();
+            println!("{}", summary);
+        } else {
+            eprintln!("{}", summary);
+        }
+    }
+
+    if errors > 0 || tally.failed > 0 {
+        std::process::exit(1);
+    }
+}
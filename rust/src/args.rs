@@ -0,0 +1,121 @@
+//! Command-line options shared by the runners for choosing a day's input
+
+use crate::{read_input, read_input_from_path, AocError, Day};
+use std::io::{self, Read};
+use std::path::PathBuf;
+
+/// Where a day's input is read from
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub enum InputSource {
+    /// `dayNN.txt` in the configured input directory
+    #[default]
+    Default,
+    File(PathBuf),
+    Stdin,
+    /// One of the day's examples, numbered from 1
+    Example(usize),
+}
+
+impl InputSource {
+    /// Split `--input FILE`, `--input -` (stdin) and `--example N` out of `args`
+    ///
+    /// Returns the input source and the remaining arguments.
+    pub fn split_args(args: &[String]) -> Result<(InputSource, Vec<String>), String> {
+        let mut source = None;
+        let mut rest = Vec::new();
+        let mut args = args.iter();
+
+        while let Some(arg) = args.next() {
+            let selected = match arg.as_str() {
+                "--input" => match args.next().map(String::as_str) {
+                    Some("-") => InputSource::Stdin,
+                    Some(path) => InputSource::File(PathBuf::from(path)),
+                    None => return Err("--input needs a file, or - for stdin".to_string()),
+                },
+                "--example" => match args.next().and_then(|n| n.parse().ok()) {
+                    Some(n) if n > 0 => InputSource::Example(n),
+                    _ => return Err("--example needs an example number, starting at 1".to_string()),
+                },
+                _ => {
+                    rest.push(arg.clone());
+                    continue;
+                }
+            };
+            if source.replace(selected).is_some() {
+                return Err("Only one of --input and --example can be given".to_string());
+            }
+        }
+        Ok((source.unwrap_or_default(), rest))
+    }
+
+    /// Whether this is the day's own puzzle input, which answers are recorded for
+    pub fn is_default(&self) -> bool {
+        *self == InputSource::Default
+    }
+
+    /// Whether the source is a single input rather than one per day
+    pub fn is_single(&self) -> bool {
+        matches!(self, InputSource::File(_) | InputSource::Stdin)
+    }
+
+    /// Read the input for `day`
+    pub fn read(&self, day: &Day) -> Result<String, AocError> {
+        match self {
+            InputSource::Default => read_input(day.number),
+            InputSource::File(path) => read_input_from_path(path),
+            InputSource::Stdin => {
+                let mut input = String::new();
+                io::stdin()
+                    .read_to_string(&mut input)
+                    .map_err(|e| AocError::io("<stdin>", e))?;
+                Ok(input)
+            }
+            InputSource::Example(n) => {
+                let examples = day.solution.examples();
+                examples
+                    .get(n - 1)
+                    .map(|example| example.to_string())
+                    .ok_or(AocError::MissingExample {
+                        day: day.number,
+                        example: *n,
+                        available: examples.len(),
+                    })
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::find_day;
+
+    fn args(args: &[&str]) -> Vec<String> {
+        args.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn test_split_args() {
+        let (source, rest) = InputSource::split_args(&args(&["7", "--input", "big.txt", "--part", "2"])).unwrap();
+        assert_eq!(source, InputSource::File(PathBuf::from("big.txt")));
+        assert_eq!(rest, args(&["7", "--part", "2"]));
+
+        assert_eq!(InputSource::split_args(&args(&["--input", "-"])).unwrap().0, InputSource::Stdin);
+        assert_eq!(InputSource::split_args(&args(&["--example", "2"])).unwrap().0, InputSource::Example(2));
+        assert_eq!(InputSource::split_args(&args(&["7"])).unwrap().0, InputSource::Default);
+
+        assert!(InputSource::split_args(&args(&["--input"])).is_err());
+        assert!(InputSource::split_args(&args(&["--example", "0"])).is_err());
+        assert!(InputSource::split_args(&args(&["--example", "1", "--input", "-"])).is_err());
+    }
+
+    #[test]
+    fn test_read_example() {
+        let day = find_day(1).unwrap();
+        let input = InputSource::Example(1).read(day).unwrap();
+        assert_eq!(day.run(1, &input).unwrap().to_string(), "3");
+
+        let err = InputSource::Example(9).read(day).unwrap_err();
+        assert!(matches!(err, AocError::MissingExample { day: 1, example: 9, available: 1 }));
+    }
+}
@@ -0,0 +1,197 @@
+//! Timing of `aoc bench`: repeated runs of each stage and a CSV history of the results
+
+use crate::error::Result;
+use crate::{AocError, Day};
+use std::fs::OpenOptions;
+use std::io::Write;
+use std::path::Path;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+/// Stages of a day that are timed separately
+pub const STAGES: [&str; 3] = ["parse", "part1", "part2"];
+
+/// Summary of repeated timings of one stage
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Stats {
+    pub min: Duration,
+    pub median: Duration,
+    pub max: Duration,
+}
+
+impl Stats {
+    /// Summarize a non-empty set of samples
+    pub fn from_samples(samples: &mut [Duration]) -> Self {
+        assert!(!samples.is_empty(), "No samples to summarize");
+        samples.sort();
+        let mid = samples.len() / 2;
+        let median = if samples.len().is_multiple_of(2) {
+            (samples[mid - 1] + samples[mid]) / 2
+        } else {
+            samples[mid]
+        };
+        Stats {
+            min: samples[0],
+            median,
+            max: samples[samples.len() - 1],
+        }
+    }
+}
+
+/// Timings of parse, part 1 and part 2 for one day, in `STAGES` order
+#[derive(Debug, Clone)]
+pub struct DayBench {
+    pub day: u8,
+    pub iterations: usize,
+    pub stages: [Stats; 3],
+}
+
+fn time<T>(f: impl FnOnce() -> T) -> (T, Duration) {
+    let start = Instant::now();
+    let value = f();
+    (value, start.elapsed())
+}
+
+/// Time each stage of `day` separately, discarding `warmup` runs first
+///
+/// The parts are timed against an input parsed once, so part timings do not
+/// include parsing.
+pub fn bench_day(day: &Day, input: &str, warmup: usize, iterations: usize) -> Result<DayBench> {
+    assert!(iterations > 0, "At least one iteration is needed");
+    let mut samples = vec![Vec::with_capacity(iterations); STAGES.len()];
+
+    for run in 0..warmup + iterations {
+        let (parsed, parse_time) = time(|| day.solution.parse(input));
+        let parsed = parsed?;
+        let (_, part1_time) = time(|| day.solution.part(1, parsed.as_ref()));
+        let (_, part2_time) = time(|| day.solution.part(2, parsed.as_ref()));
+
+        if run >= warmup {
+            samples[0].push(parse_time);
+            samples[1].push(part1_time);
+            samples[2].push(part2_time);
+        }
+    }
+
+    Ok(DayBench {
+        day: day.number,
+        iterations,
+        stages: [
+            Stats::from_samples(&mut samples[0]),
+            Stats::from_samples(&mut samples[1]),
+            Stats::from_samples(&mut samples[2]),
+        ],
+    })
+}
+
+/// Header of the CSV results file
+pub const CSV_HEADER: &str = "timestamp,day,stage,iterations,min_ns,median_ns,max_ns";
+
+impl DayBench {
+    /// One CSV row per stage, stamped with `timestamp` (seconds since the epoch)
+    pub fn csv_rows(&self, timestamp: u64) -> Vec<String> {
+        STAGES
+            .iter()
+            .zip(&self.stages)
+            .map(|(stage, stats)| {
+                format!(
+                    "{},{},{},{},{},{},{}",
+                    timestamp,
+                    self.day,
+                    stage,
+                    self.iterations,
+                    stats.min.as_nanos(),
+                    stats.median.as_nanos(),
+                    stats.max.as_nanos()
+                )
+            })
+            .collect()
+    }
+}
+
+/// Append the results to a CSV file, writing the header if the file is new
+///
+/// Appending keeps earlier runs so performance can be tracked over time.
+pub fn append_csv(path: &Path, results: &[DayBench]) -> Result<()> {
+    let timestamp = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |d| d.as_secs());
+    let is_new = !path.exists();
+
+    let mut file = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)
+        .map_err(|e| AocError::io(path, e))?;
+
+    let mut text = String::new();
+    if is_new {
+        text.push_str(CSV_HEADER);
+        text.push('\n');
+    }
+    for result in results {
+        for row in result.csv_rows(timestamp) {
+            text.push_str(&row);
+            text.push('\n');
+        }
+    }
+    file.write_all(text.as_bytes()).map_err(|e| AocError::io(path, e))
+}
+
+/// Format a duration with a unit suited to its size
+pub fn format_duration(d: Duration) -> String {
+    let nanos = d.as_nanos();
+    if nanos < 1_000 {
+        format!("{} ns", nanos)
+    } else if nanos < 1_000_000 {
+        format!("{:.1} µs", nanos as f64 / 1e3)
+    } else if nanos < 1_000_000_000 {
+        format!("{:.1} ms", nanos as f64 / 1e6)
+    } else {
+        format!("{:.2} s", nanos as f64 / 1e9)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::find_day;
+
+    fn ms(n: u64) -> Duration {
+        Duration::from_millis(n)
+    }
+
+    #[test]
+    fn test_stats_odd_and_even() {
+        let stats = Stats::from_samples(&mut [ms(5), ms(1), ms(3)]);
+        assert_eq!(stats, Stats { min: ms(1), median: ms(3), max: ms(5) });
+
+        let stats = Stats::from_samples(&mut [ms(4), ms(1), ms(2), ms(8)]);
+        assert_eq!(stats.median, ms(3));
+    }
+
+    #[test]
+    fn test_bench_day_and_csv() {
+        let day = find_day(1).unwrap();
+        let result = bench_day(day, "+1\n-2\n+3\n", 1, 3).unwrap();
+        assert_eq!(result.day, 1);
+        assert_eq!(result.iterations, 3);
+
+        let rows = result.csv_rows(1700000000);
+        assert_eq!(rows.len(), 3);
+        assert!(rows[1].starts_with("1700000000,1,part1,3,"));
+        assert_eq!(rows[2].split(',').count(), CSV_HEADER.split(',').count());
+    }
+
+    #[test]
+    fn test_bench_day_reports_parse_errors() {
+        let day = find_day(1).unwrap();
+        assert!(bench_day(day, "+x\n", 0, 1).is_err());
+    }
+
+    #[test]
+    fn test_format_duration() {
+        assert_eq!(format_duration(Duration::from_nanos(950)), "950 ns");
+        assert_eq!(format_duration(Duration::from_micros(1500)), "1.5 ms");
+        assert_eq!(format_duration(Duration::from_secs(3)), "3.00 s");
+    }
+}
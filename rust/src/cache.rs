@@ -0,0 +1,225 @@
+//! Manifest of downloaded inputs and throttling of requests to the AoC server
+
+use crate::AocError;
+use std::collections::BTreeMap;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::thread;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// 64-bit FNV-1a hash of `bytes` as 16 hex digits
+pub fn content_hash(bytes: &[u8]) -> String {
+    let hash = bytes.iter().fold(0xcbf2_9ce4_8422_2325u64, |hash, &b| {
+        (hash ^ b as u64).wrapping_mul(0x0000_0100_0000_01b3)
+    });
+    format!("{:016x}", hash)
+}
+
+fn now() -> Duration {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+}
+
+/// Check that downloaded input is puzzle input rather than an error page
+pub fn validate_input(content: &str) -> Result<(), String> {
+    let start = content.trim_start().to_ascii_lowercase();
+    let errors = [
+        "puzzle inputs differ by user",
+        "please don't repeatedly request this endpoint",
+        "404 not found",
+        "500 internal server error",
+    ];
+
+    if content.trim().is_empty() {
+        Err("the response is empty".to_string())
+    } else if start.starts_with("<!doctype") || start.starts_with("<html") {
+        Err("the response is an HTML page".to_string())
+    } else if let Some(error) = errors.iter().find(|error| start.starts_with(*error)) {
+        Err(format!("the response is an error message ({:?})", error))
+    } else if !content.ends_with('\n') {
+        Err("the response does not end with a newline, it may be truncated".to_string())
+    } else {
+        Ok(())
+    }
+}
+
+/// What is recorded about one downloaded input
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Entry {
+    pub hash: String,
+    pub size: u64,
+    /// Seconds since the epoch
+    pub fetched: u64,
+}
+
+/// How an input file compares with its manifest entry
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Status {
+    Valid,
+    /// The file differs from what was downloaded, e.g. truncated or edited
+    Changed { expected: Entry },
+    /// The file was not downloaded by `prepare`
+    Unknown,
+}
+
+/// Manifest of downloaded inputs, stored as `manifest.tsv` in the input directory
+/// with tab separated `file hash size fetched` lines
+///
+/// Files are recorded by whatever key the caller uses; `prepare` uses
+/// `year/file` so inputs of different years don't mix.
+#[derive(Debug, Clone, Default)]
+pub struct Manifest {
+    path: PathBuf,
+    entries: BTreeMap<String, Entry>,
+}
+
+impl Manifest {
+    pub fn load(input_dir: &Path) -> Result<Self, AocError> {
+        let path = input_dir.join("manifest.tsv");
+        let text = match fs::read_to_string(&path) {
+            Ok(text) => text,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => String::new(),
+            Err(e) => return Err(AocError::io(path, e)),
+        };
+
+        let entries = text
+            .lines()
+            .filter(|line| !line.starts_with('#'))
+            .filter_map(|line| {
+                let fields: Vec<&str> = line.split('\t').collect();
+                let [file, hash, size, fetched] = fields[..] else {
+                    return None;
+                };
+                let entry = Entry {
+                    hash: hash.to_string(),
+                    size: size.parse().ok()?,
+                    fetched: fetched.parse().ok()?,
+                };
+                Some((file.to_string(), entry))
+            })
+            .collect();
+        Ok(Manifest { path, entries })
+    }
+
+    pub fn get(&self, file: &str) -> Option<&Entry> {
+        self.entries.get(file)
+    }
+
+    /// Compare `content` of `file` with the manifest
+    pub fn check(&self, file: &str, content: &[u8]) -> Status {
+        match self.entries.get(file) {
+            None => Status::Unknown,
+            Some(entry) if entry.size == content.len() as u64 && entry.hash == content_hash(content) => {
+                Status::Valid
+            }
+            Some(entry) => Status::Changed {
+                expected: entry.clone(),
+            },
+        }
+    }
+
+    /// Record `content` as freshly downloaded for `file` and save the manifest
+    pub fn record(&mut self, file: &str, content: &[u8]) -> Result<(), AocError> {
+        let entry = Entry {
+            hash: content_hash(content),
+            size: content.len() as u64,
+            fetched: now().as_secs(),
+        };
+        self.entries.insert(file.to_string(), entry);
+
+        let mut text = String::from("# file\thash\tsize\tfetched\n");
+        for (file, entry) in &self.entries {
+            text.push_str(&format!("{}\t{}\t{}\t{}\n", file, entry.hash, entry.size, entry.fetched));
+        }
+        fs::write(&self.path, text).map_err(|e| AocError::io(&self.path, e))
+    }
+}
+
+/// Keeps requests at least `interval` apart, also across runs, by storing the
+/// time of the last request in a file
+#[derive(Debug, Clone)]
+pub struct Throttle {
+    path: PathBuf,
+    interval: Duration,
+}
+
+impl Throttle {
+    pub fn new(path: impl Into<PathBuf>, interval: Duration) -> Self {
+        Throttle {
+            path: path.into(),
+            interval,
+        }
+    }
+
+    /// Sleep until the interval since the last request has passed, then record
+    /// this request; returns how long it slept
+    pub fn wait(&self) -> Duration {
+        let last = fs::read_to_string(&self.path)
+            .ok()
+            .and_then(|text| text.trim().parse().ok())
+            .map(Duration::from_millis);
+
+        let elapsed = last.map_or(self.interval, |last| now().saturating_sub(last));
+        let delay = self.interval.saturating_sub(elapsed);
+        thread::sleep(delay);
+
+        if let Some(dir) = self.path.parent() {
+            let _ = fs::create_dir_all(dir);
+        }
+        let _ = fs::write(&self.path, now().as_millis().to_string());
+        delay
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("aoc2018-cache-{}-{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[test]
+    fn test_content_hash() {
+        assert_eq!(content_hash(b""), "cbf29ce484222325");
+        assert_eq!(content_hash(b"a"), "af63dc4c8601ec8c");
+        assert_ne!(content_hash(b"+1\n-2\n"), content_hash(b"+1\n-2"));
+    }
+
+    #[test]
+    fn test_validate_input() {
+        assert!(validate_input("+1\n-2\n").is_ok());
+        assert!(validate_input("").is_err());
+        assert!(validate_input("<!DOCTYPE html>\n<html>").is_err());
+        assert!(validate_input("Puzzle inputs differ by user.  Please log in to get your puzzle input.\n").is_err());
+        assert!(validate_input("+1\n-").is_err());
+    }
+
+    #[test]
+    fn test_manifest_round_trip() {
+        let dir = temp_dir("manifest");
+        let mut manifest = Manifest::load(&dir).unwrap();
+        assert_eq!(manifest.check("day01.txt", b"+1\n"), Status::Unknown);
+
+        manifest.record("day01.txt", b"+1\n").unwrap();
+        let manifest = Manifest::load(&dir).unwrap();
+        assert_eq!(manifest.check("day01.txt", b"+1\n"), Status::Valid);
+        assert!(matches!(manifest.check("day01.txt", b"+1"), Status::Changed { .. }));
+        assert_eq!(manifest.get("day01.txt").unwrap().size, 3);
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_throttle() {
+        let dir = temp_dir("throttle");
+        let throttle = Throttle::new(dir.join(".last-request"), Duration::from_millis(200));
+        assert_eq!(throttle.wait(), Duration::ZERO);
+        assert!(throttle.wait() > Duration::from_millis(100));
+        fs::remove_dir_all(dir).unwrap();
+    }
+}
@@ -0,0 +1,300 @@
+//! Layered settings: command-line flag, then environment, then the project
+//! `.env`, then the user config file under `$XDG_CONFIG_HOME`
+//!
+//! Every setting has an environment-style name such as `AOC_SESSION`, used
+//! both as the environment variable and as the key in `.env` and config files.
+
+use crate::AocError;
+use std::collections::HashMap;
+use std::env;
+use std::fs;
+use std::path::PathBuf;
+use std::time::Duration;
+
+/// A setting and the command-line flag that overrides it
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Setting {
+    pub name: &'static str,
+    pub flag: &'static str,
+}
+
+pub const SESSION: Setting = Setting {
+    name: "AOC_SESSION",
+    flag: "--session",
+};
+pub const YEAR: Setting = Setting {
+    name: "AOC_YEAR",
+    flag: "--year",
+};
+pub const USER_AGENT: Setting = Setting {
+    name: "AOC_USER_AGENT",
+    flag: "--user-agent",
+};
+pub const INPUT_DIR: Setting = Setting {
+    name: "AOC_INPUT_DIR",
+    flag: "--input-dir",
+};
+pub const BASE_URL: Setting = Setting {
+    name: "AOC_BASE_URL",
+    flag: "--base-url",
+};
+
+pub const REQUEST_INTERVAL: Setting = Setting {
+    name: "AOC_REQUEST_INTERVAL",
+    flag: "--request-interval",
+};
+
+/// All settings that can be given as flags
+pub const SETTINGS: [Setting; 6] = [SESSION, YEAR, USER_AGENT, INPUT_DIR, BASE_URL, REQUEST_INTERVAL];
+
+/// A `KEY=VALUE` file; `None` values mean the file could not be read
+#[derive(Debug, Clone)]
+struct ConfigFile {
+    path: PathBuf,
+    values: Option<HashMap<String, String>>,
+}
+
+impl ConfigFile {
+    fn load(path: PathBuf) -> Self {
+        let values = fs::read_to_string(&path).ok().map(|text| parse_env_file(&text));
+        ConfigFile { path, values }
+    }
+
+    fn describe(&self) -> String {
+        match self.values {
+            Some(_) => format!("{}", self.path.display()),
+            None => format!("{} (not found)", self.path.display()),
+        }
+    }
+}
+
+/// Parse `.env` style `KEY=VALUE` lines, skipping blanks and `#` comments
+pub fn parse_env_file(text: &str) -> HashMap<String, String> {
+    text.lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .filter_map(|line| {
+            let line = line.strip_prefix("export ").unwrap_or(line);
+            let (key, value) = line.split_once('=')?;
+            let value = value.trim();
+            let value = value
+                .strip_prefix('"')
+                .and_then(|v| v.strip_suffix('"'))
+                .or_else(|| value.strip_prefix('\'').and_then(|v| v.strip_suffix('\'')))
+                .unwrap_or(value);
+            Some((key.trim().to_string(), value.to_string()))
+        })
+        .collect()
+}
+
+/// Location of the user config file: `$XDG_CONFIG_HOME/aoc2018/config`,
+/// falling back to `~/.config/aoc2018/config`
+pub fn user_config_path() -> Option<PathBuf> {
+    let dir = env::var_os("XDG_CONFIG_HOME")
+        .filter(|dir| !dir.is_empty())
+        .map(PathBuf::from)
+        .or_else(|| env::var_os("HOME").map(|home| PathBuf::from(home).join(".config")))?;
+    Some(dir.join("aoc2018").join("config"))
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct Config {
+    flags: HashMap<&'static str, String>,
+    env: HashMap<String, String>,
+    files: Vec<ConfigFile>,
+}
+
+impl Config {
+    /// Settings from `flags` (setting name to value), the process environment,
+    /// `.env` in the current directory and the user config file
+    pub fn load(flags: HashMap<&'static str, String>) -> Self {
+        let mut files = vec![PathBuf::from(".env")];
+        files.extend(user_config_path());
+        Config::from_sources(flags, env::vars().collect(), files)
+    }
+
+    /// Settings from explicit sources, `files` in priority order
+    pub fn from_sources(
+        flags: HashMap<&'static str, String>,
+        env: HashMap<String, String>,
+        files: Vec<PathBuf>,
+    ) -> Self {
+        Config {
+            flags,
+            env,
+            files: files.into_iter().map(ConfigFile::load).collect(),
+        }
+    }
+
+    /// Split `--flag value` pairs for the known settings out of `args`
+    ///
+    /// Returns the flags by setting name and the remaining arguments.
+    pub fn split_flags(args: &[String]) -> Result<(HashMap<&'static str, String>, Vec<String>), String> {
+        let mut flags = HashMap::new();
+        let mut rest = Vec::new();
+        let mut args = args.iter();
+
+        while let Some(arg) = args.next() {
+            match SETTINGS.iter().find(|setting| setting.flag == arg) {
+                Some(setting) => {
+                    let value = args.next().ok_or_else(|| format!("{} needs a value", arg))?;
+                    flags.insert(setting.name, value.clone());
+                }
+                None => rest.push(arg.clone()),
+            }
+        }
+        Ok((flags, rest))
+    }
+
+    /// The value of a setting from the first source that has it
+    pub fn get(&self, setting: Setting) -> Option<String> {
+        self.flags
+            .get(setting.name)
+            .or_else(|| self.env.get(setting.name))
+            .or_else(|| {
+                self.files
+                    .iter()
+                    .find_map(|file| file.values.as_ref()?.get(setting.name))
+            })
+            .filter(|value| !value.is_empty())
+            .cloned()
+    }
+
+    /// The value of a setting, or an error listing every source that was checked
+    pub fn require(&self, setting: Setting) -> Result<String, AocError> {
+        self.get(setting).ok_or_else(|| {
+            let mut checked = vec![
+                format!("{} flag", setting.flag),
+                format!("{} environment variable", setting.name),
+            ];
+            checked.extend(self.files.iter().map(ConfigFile::describe));
+            AocError::MissingSetting {
+                name: setting.name,
+                checked,
+            }
+        })
+    }
+
+    pub fn session(&self) -> Result<String, AocError> {
+        self.require(SESSION)
+    }
+
+    pub fn year(&self) -> Result<u16, AocError> {
+        match self.get(YEAR) {
+            None => Ok(crate::YEAR),
+            Some(year) => year.parse().map_err(|e| AocError::InvalidSetting {
+                name: YEAR.name,
+                message: format!("{:?} is not a year: {}", year, e),
+            }),
+        }
+    }
+
+    pub fn user_agent(&self) -> String {
+        self.get(USER_AGENT)
+            .unwrap_or_else(|| "github.com/aoc2018 by muzta".to_string())
+    }
+
+    pub fn input_dir(&self) -> PathBuf {
+        PathBuf::from(self.get(INPUT_DIR).unwrap_or_else(|| "inputs".to_string()))
+    }
+
+    /// Minimum time between requests to the AoC server, in seconds (default 3)
+    pub fn request_interval(&self) -> Result<Duration, AocError> {
+        match self.get(REQUEST_INTERVAL) {
+            None => Ok(Duration::from_secs(3)),
+            Some(seconds) => seconds
+                .parse()
+                .ok()
+                .and_then(|seconds| Duration::try_from_secs_f64(seconds).ok())
+                .ok_or_else(|| AocError::InvalidSetting {
+                    name: REQUEST_INTERVAL.name,
+                    message: format!("{:?} is not a number of seconds", seconds),
+                }),
+        }
+    }
+
+    pub fn base_url(&self) -> String {
+        self.get(BASE_URL)
+            .map(|url| url.trim_end_matches('/').to_string())
+            .unwrap_or_else(|| "https://adventofcode.com".to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_file(name: &str, contents: &str) -> PathBuf {
+        let path = env::temp_dir().join(format!("aoc2018-config-{}-{}", name, std::process::id()));
+        fs::write(&path, contents).unwrap();
+        path
+    }
+
+    #[test]
+    fn test_parse_env_file() {
+        let values = parse_env_file("# comment\n\nAOC_SESSION=abc\nexport AOC_YEAR = \"2019\"\nAOC_USER_AGENT='me'\n");
+        assert_eq!(values["AOC_SESSION"], "abc");
+        assert_eq!(values["AOC_YEAR"], "2019");
+        assert_eq!(values["AOC_USER_AGENT"], "me");
+        assert_eq!(values.len(), 3);
+    }
+
+    #[test]
+    fn test_priority_order() {
+        let dotenv = temp_file("dotenv", "AOC_SESSION=dotenv\nAOC_YEAR=2017\nAOC_INPUT_DIR=data\n");
+        let user = temp_file("user", "AOC_SESSION=user\nAOC_YEAR=2016\nAOC_USER_AGENT=agent\n");
+        let flags = HashMap::from([("AOC_SESSION", "flag".to_string())]);
+        let env = HashMap::from([
+            ("AOC_SESSION".to_string(), "env".to_string()),
+            ("AOC_YEAR".to_string(), "2019".to_string()),
+        ]);
+
+        let config = Config::from_sources(flags, env.clone(), vec![dotenv.clone(), user.clone()]);
+        assert_eq!(config.session().unwrap(), "flag");
+        assert_eq!(config.year().unwrap(), 2019);
+        assert_eq!(config.input_dir(), PathBuf::from("data"));
+        assert_eq!(config.user_agent(), "agent");
+        assert_eq!(config.base_url(), "https://adventofcode.com");
+
+        let config = Config::from_sources(HashMap::new(), env, vec![dotenv.clone(), user.clone()]);
+        assert_eq!(config.session().unwrap(), "env");
+
+        let config = Config::from_sources(HashMap::new(), HashMap::new(), vec![dotenv.clone(), user.clone()]);
+        assert_eq!(config.session().unwrap(), "dotenv");
+
+        let config = Config::from_sources(HashMap::new(), HashMap::new(), vec![user.clone()]);
+        assert_eq!(config.session().unwrap(), "user");
+
+        fs::remove_file(dotenv).unwrap();
+        fs::remove_file(user).unwrap();
+    }
+
+    #[test]
+    fn test_missing_setting_lists_sources() {
+        let dotenv = temp_file("empty", "AOC_YEAR=2018\n");
+        let config = Config::from_sources(
+            HashMap::new(),
+            HashMap::new(),
+            vec![dotenv.clone(), PathBuf::from("/nonexistent/aoc2018/config")],
+        );
+        let message = config.session().unwrap_err().to_string();
+        assert!(message.contains("--session flag"));
+        assert!(message.contains("AOC_SESSION environment variable"));
+        assert!(message.contains(&dotenv.display().to_string()));
+        assert!(message.contains("/nonexistent/aoc2018/config (not found)"));
+        fs::remove_file(dotenv).unwrap();
+    }
+
+    #[test]
+    fn test_split_flags() {
+        let args: Vec<String> = ["--year", "2017", "3", "--session", "abc"]
+            .iter()
+            .map(|s| s.to_string())
+            .collect();
+        let (flags, rest) = Config::split_flags(&args).unwrap();
+        assert_eq!(flags["AOC_YEAR"], "2017");
+        assert_eq!(flags["AOC_SESSION"], "abc");
+        assert_eq!(rest, vec!["3"]);
+        assert!(Config::split_flags(&["--year".to_string()]).is_err());
+    }
+}
@@ -0,0 +1,191 @@
+//! Cycle detection for simulations that eventually repeat, to skip ahead to
+//! step counts far too large to simulate
+//!
+//! A simulation is an `initial` state and a `step` function. Step 0 is the
+//! initial state and step `n` the state after `n` calls of `step`.
+
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashMap;
+use std::hash::{Hash, Hasher};
+
+/// The steps from `start` on repeat every `length` steps
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Cycle {
+    pub start: usize,
+    pub length: usize,
+}
+
+impl Cycle {
+    /// The first step with the same state as step `n`
+    pub fn equivalent_step(&self, n: u64) -> usize {
+        let (start, length) = (self.start as u64, self.length as u64);
+        if n < start {
+            n as usize
+        } else {
+            (start + (n - start) % length) as usize
+        }
+    }
+
+    /// Number of whole cycles from `equivalent_step(n)` to step `n`
+    pub fn full_cycles(&self, n: u64) -> u64 {
+        n.saturating_sub(self.start as u64) / self.length as u64
+    }
+}
+
+/// Find the cycle by remembering a fingerprint of every state until one repeats
+///
+/// Each step is simulated once. The fingerprint can be the state itself, or
+/// something smaller or coarser, e.g. a shape without its position, as long as
+/// states with equal fingerprints go on to equal fingerprints. Never returns if
+/// the fingerprints don't repeat.
+pub fn find_cycle<S, K>(initial: S, step: impl FnMut(&S) -> S, fingerprint: impl FnMut(&S) -> K) -> Cycle
+where
+    K: Eq + Hash,
+{
+    run(initial, u64::MAX, step, fingerprint).1.unwrap()
+}
+
+/// Step until a fingerprint repeats or `limit` steps are done, returning the
+/// state reached and the cycle if one was found
+///
+/// When a cycle is found, the state is the one at step `start + length`.
+fn run<S, K>(initial: S, limit: u64, mut step: impl FnMut(&S) -> S, mut fingerprint: impl FnMut(&S) -> K) -> (S, Option<Cycle>)
+where
+    K: Eq + Hash,
+{
+    let mut seen = HashMap::new();
+    let mut state = initial;
+    let mut n = 0;
+    while (n as u64) < limit {
+        if let Some(start) = seen.insert(fingerprint(&state), n) {
+            return (state, Some(Cycle { start, length: n - start }));
+        }
+        state = step(&state);
+        n += 1;
+    }
+    (state, None)
+}
+
+/// A 64-bit hash of `state`, a fingerprint that is cheap to store for large
+/// states
+///
+/// Different states could in theory share a hash, but with 64 bits that is
+/// far too unlikely to matter for the few thousand states of a puzzle.
+pub fn hash<S: Hash>(state: &S) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    state.hash(&mut hasher);
+    hasher.finish()
+}
+
+/// Find the cycle with Brent's algorithm, which keeps only two states at a time
+///
+/// Takes up to about three times as many steps as `find_cycle`, so it suits
+/// states that are cheap to step but expensive to store. Never returns if the
+/// states don't repeat.
+pub fn brent<S: Clone + PartialEq>(initial: &S, mut step: impl FnMut(&S) -> S) -> Cycle {
+    // Find the length: the hare runs ahead and the tortoise teleports to it
+    // whenever the distance between them reaches a power of two
+    let mut power = 1;
+    let mut length = 1;
+    let mut tortoise = initial.clone();
+    let mut hare = step(initial);
+    while tortoise != hare {
+        if power == length {
+            tortoise = hare.clone();
+            power *= 2;
+            length = 0;
+        }
+        hare = step(&hare);
+        length += 1;
+    }
+
+    // Find the start: walk two states `length` apart until they meet
+    let mut tortoise = initial.clone();
+    let mut hare = initial.clone();
+    for _ in 0..length {
+        hare = step(&hare);
+    }
+    let mut start = 0;
+    while tortoise != hare {
+        tortoise = step(&tortoise);
+        hare = step(&hare);
+        start += 1;
+    }
+    Cycle { start, length }
+}
+
+/// The state after `n` steps, simulating until the first repeat of a
+/// fingerprint and then skipping the whole cycles
+///
+/// Only the fingerprints are stored, so `hash` suits large states. Unlike for
+/// `find_cycle`, states with equal fingerprints must be equal, or the result is
+/// only a state that looks like step `n`.
+pub fn nth_state<S, K>(initial: S, n: u64, mut step: impl FnMut(&S) -> S, fingerprint: impl FnMut(&S) -> K) -> S
+where
+    K: Eq + Hash,
+{
+    let (mut state, cycle) = run(initial, n, &mut step, fingerprint);
+    if let Some(cycle) = cycle {
+        // The state is the one at `start + length`, which equals the one at `start`
+        for _ in cycle.start..cycle.equivalent_step(n) {
+            state = step(&state);
+        }
+    }
+    state
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 3, 10, 5, 16, 8, 4, 2, 1, 4, 2, 1, ...
+    fn collatz(&n: &u64) -> u64 {
+        if n % 2 == 0 {
+            n / 2
+        } else {
+            3 * n + 1
+        }
+    }
+
+    #[test]
+    fn test_find_cycle() {
+        let cycle = Cycle { start: 5, length: 3 };
+        assert_eq!(find_cycle(3, collatz, |&n| n), cycle);
+        assert_eq!(brent(&3, collatz), cycle);
+        assert_eq!(brent(&1, collatz), Cycle { start: 0, length: 3 });
+
+        // Something moving along while cycling through 3 phases never repeats
+        // exactly, but its phase does
+        let moving = |&(x, phase): &(i64, u8)| (x + 2, (phase + 1) % 3);
+        assert_eq!(find_cycle((0, 1), moving, |&(_, phase)| phase), Cycle { start: 0, length: 3 });
+    }
+
+    #[test]
+    fn test_equivalent_step() {
+        let cycle = Cycle { start: 5, length: 3 };
+        assert_eq!(cycle.equivalent_step(2), 2);
+        assert_eq!(cycle.equivalent_step(7), 7);
+        assert_eq!(cycle.equivalent_step(8), 5);
+        assert_eq!(cycle.equivalent_step(1_000_000_000_000), 7);
+        assert_eq!(cycle.full_cycles(7), 0);
+        assert_eq!(cycle.full_cycles(1_000_000_000_000), 333_333_333_331);
+    }
+
+    #[test]
+    fn test_nth_state() {
+        assert_eq!(nth_state(3, 0, collatz, |&n| n), 3);
+        assert_eq!(nth_state(3, 4, collatz, |&n| n), 8);
+        assert_eq!(nth_state(3, 8, collatz, |&n| n), 4);
+        assert_eq!(nth_state(3, 1_000_000_000_000, collatz, |&n| n), 1);
+        assert_eq!(nth_state(3, 1_000_000_000_001, collatz, hash), 4);
+
+        let mut steps = 0;
+        let counted = |n: &u64| {
+            steps += 1;
+            collatz(n)
+        };
+        assert_eq!(nth_state(3, 1_000_000_000_002, counted, hash), 2);
+        // Eight steps to see 4 again, then one more
+        assert_eq!(steps, 9);
+    }
+}
@@ -1,19 +1,48 @@
-use aoc2018::read_input;
+use crate::error::{parse_at, Result};
+use crate::{debug, Answer, Solution};
 use std::collections::HashSet;
 
-fn part1(input: &str) -> i32 {
+pub struct Day01;
+
+/// The examples from the puzzle description, for `aoc run --example N`
+const EXAMPLES: &[&str] = &[
+    "+1\n-2\n+3\n+1",
+];
+
+impl Solution for Day01 {
+    type Input = Vec<i32>;
+
+    fn parse(&self, input: &str) -> Result<Vec<i32>> {
+        parse_input(input)
+    }
+
+    fn part1(&self, changes: &Vec<i32>) -> Answer {
+        part1(changes).into()
+    }
+
+    fn part2(&self, changes: &Vec<i32>) -> Answer {
+        part2(changes).into()
+    }
+
+    fn examples(&self) -> &'static [&'static str] {
+        EXAMPLES
+    }
+}
+
+fn parse_input(input: &str) -> Result<Vec<i32>> {
     input
         .lines()
-        .filter_map(|line| line.trim().parse::<i32>().ok())
-        .sum()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .map(|line| parse_at(input, line))
+        .collect()
 }
 
-fn part2(input: &str) -> i32 {
-    let changes: Vec<i32> = input
-        .lines()
-        .filter_map(|line| line.trim().parse::<i32>().ok())
-        .collect();
+fn part1(changes: &[i32]) -> i32 {
+    changes.iter().sum()
+}
 
+fn part2(changes: &[i32]) -> i32 {
     let mut frequency = 0;
     let mut seen = HashSet::new();
     seen.insert(0);
@@ -21,6 +50,7 @@ fn part2(input: &str) -> i32 {
     for change in changes.iter().cycle() {
         frequency += change;
         if !seen.insert(frequency) {
+            debug!("{} repeats after {} changes", frequency, seen.len());
             return frequency;
         }
     }
@@ -28,31 +58,24 @@ fn part2(input: &str) -> i32 {
     unreachable!()
 }
 
-fn main() {
-    let input = read_input(1);
-
-    println!("Part 1: {}", part1(&input));
-    println!("Part 2: {}", part2(&input));
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
 
     #[test]
     fn test_part1() {
-        assert_eq!(part1("+1\n-2\n+3\n+1"), 3);
-        assert_eq!(part1("+1\n+1\n+1"), 3);
-        assert_eq!(part1("+1\n+1\n-2"), 0);
-        assert_eq!(part1("-1\n-2\n-3"), -6);
+        assert_eq!(part1(&parse_input(EXAMPLES[0]).unwrap()), 3);
+        assert_eq!(part1(&parse_input("+1\n+1\n+1").unwrap()), 3);
+        assert_eq!(part1(&parse_input("+1\n+1\n-2").unwrap()), 0);
+        assert_eq!(part1(&parse_input("-1\n-2\n-3").unwrap()), -6);
     }
 
     #[test]
     fn test_part2() {
-        assert_eq!(part2("+1\n-2\n+3\n+1"), 2);
-        assert_eq!(part2("+1\n-1"), 0);
-        assert_eq!(part2("+3\n+3\n+4\n-2\n-4"), 10);
-        assert_eq!(part2("-6\n+3\n+8\n+5\n-6"), 5);
-        assert_eq!(part2("+7\n+7\n-2\n-7\n-4"), 14);
+        assert_eq!(part2(&parse_input(EXAMPLES[0]).unwrap()), 2);
+        assert_eq!(part2(&parse_input("+1\n-1").unwrap()), 0);
+        assert_eq!(part2(&parse_input("+3\n+3\n+4\n-2\n-4").unwrap()), 10);
+        assert_eq!(part2(&parse_input("-6\n+3\n+8\n+5\n-6").unwrap()), 5);
+        assert_eq!(part2(&parse_input("+7\n+7\n-2\n-7\n-4").unwrap()), 14);
     }
 }
@@ -1,25 +1,36 @@
-use aoc2018::read_input;
+use crate::error::Result;
+use crate::{Answer, Solution};
 
-fn main() {
-    let input = read_input(2);
+pub struct Day02;
 
-    println!("Part 1: {}", part1(&input));
-    println!("Part 2: {}", part2(&input));
+impl Solution for Day02 {
+    type Input = Vec<String>;
+
+    fn parse(&self, input: &str) -> Result<Vec<String>> {
+        Ok(input.lines().map(|line| line.trim().to_string()).collect())
+    }
+
+    fn part1(&self, ids: &Vec<String>) -> Answer {
+        part1(ids).into()
+    }
+
+    fn part2(&self, ids: &Vec<String>) -> Answer {
+        part2(ids).into()
+    }
 }
 
-fn part1(input: &str) -> usize {
+fn part1(_ids: &[String]) -> usize {
     // TODO: Implement solution for day 2 part 1
     0
 }
 
-fn part2(input: &str) -> String {
+fn part2(_ids: &[String]) -> String {
     // TODO: Implement solution for day 2 part 2
     String::new()
 }
 
 #[cfg(test)]
 mod tests {
-    use super::*;
 
     #[test]
     fn test_part1() {
@@ -1,47 +1,61 @@
-use aoc2018::read_input;
+use crate::error::{parse_at, AocError, Result};
+use crate::geom::{BoundingBox, Point2};
+use crate::parse::captures;
+use crate::{debug, Answer, Solution};
 use std::collections::{HashMap, HashSet};
 
-fn main() {
-    let input = read_input(6);
+pub struct Day06;
 
-    println!("Part 1: {}", part1(&input));
-    println!("Part 2: {}", part2(&input, 10000));
-}
+/// The examples from the puzzle description, for `aoc run --example N`
+const EXAMPLES: &[&str] = &[
+    "\
+1, 1
+1, 6
+8, 3
+3, 4
+5, 5
+8, 9",
+];
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
-struct Point {
-    x: i32,
-    y: i32,
-}
+impl Solution for Day06 {
+    type Input = Vec<Point2>;
+
+    fn parse(&self, input: &str) -> Result<Vec<Point2>> {
+        parse_input(input)
+    }
 
-impl Point {
-    fn manhattan_distance(&self, other: &Point) -> i32 {
-        (self.x - other.x).abs() + (self.y - other.y).abs()
+    fn part1(&self, points: &Vec<Point2>) -> Answer {
+        part1(points).into()
+    }
+
+    fn part2(&self, points: &Vec<Point2>) -> Answer {
+        safe_region_size(points, 10_000).into()
+    }
+
+    fn examples(&self) -> &'static [&'static str] {
+        EXAMPLES
     }
 }
 
-fn parse_input(input: &str) -> Vec<Point> {
-    input
+fn parse_input(input: &str) -> Result<Vec<Point2>> {
+    let points: Vec<Point2> = input
         .lines()
         .filter(|line| !line.is_empty())
         .map(|line| {
-            let parts: Vec<&str> = line.split(',').collect();
-            Point {
-                x: parts[0].trim().parse().unwrap(),
-                y: parts[1].trim().parse().unwrap(),
-            }
+            let [x, y] = captures(input, line, "{}, {}")?;
+            Ok(Point2::new([parse_at(input, x)?, parse_at(input, y)?]))
         })
-        .collect()
+        .collect::<Result<_>>()?;
+
+    if points.is_empty() {
+        return Err(AocError::at(input, input, "no coordinates in input"));
+    }
+    Ok(points)
 }
 
-fn part1(input: &str) -> usize {
-    let points = parse_input(input);
-    
+fn part1(points: &[Point2]) -> usize {
     // Find the bounding box
-    let min_x = points.iter().map(|p| p.x).min().unwrap();
-    let max_x = points.iter().map(|p| p.x).max().unwrap();
-    let min_y = points.iter().map(|p| p.y).min().unwrap();
-    let max_y = points.iter().map(|p| p.y).max().unwrap();
+    let bounds = BoundingBox::from_points(points.iter().copied()).unwrap();
     
     // Track which points have infinite areas (touch the boundary)
     let mut infinite_areas = HashSet::new();
@@ -49,40 +63,44 @@ fn part1(input: &str) -> usize {
     // Count area for each point
     let mut areas: HashMap<usize, usize> = HashMap::new();
     
-    for x in min_x..=max_x {
-        for y in min_y..=max_y {
-            let current = Point { x, y };
-            
-            // Find the closest point
-            let mut min_dist = i32::MAX;
-            let mut closest_idx = None;
-            let mut tie = false;
-            
-            for (idx, point) in points.iter().enumerate() {
-                let dist = current.manhattan_distance(point);
-                if dist < min_dist {
-                    min_dist = dist;
-                    closest_idx = Some(idx);
-                    tie = false;
-                } else if dist == min_dist {
-                    tie = true;
-                }
+    for current in bounds.points() {
+        // Find the closest point
+        let mut min_dist = i64::MAX;
+        let mut closest_idx = None;
+        let mut tie = false;
+        
+        for (idx, point) in points.iter().enumerate() {
+            let dist = current.manhattan(point);
+            if dist < min_dist {
+                min_dist = dist;
+                closest_idx = Some(idx);
+                tie = false;
+            } else if dist == min_dist {
+                tie = true;
             }
-            
-            // If not a tie, count this location for the closest point
-            if !tie {
-                if let Some(idx) = closest_idx {
-                    *areas.entry(idx).or_insert(0) += 1;
-                    
-                    // If on the boundary, this area is infinite
-                    if x == min_x || x == max_x || y == min_y || y == max_y {
-                        infinite_areas.insert(idx);
-                    }
+        }
+        
+        // If not a tie, count this location for the closest point
+        if !tie {
+            if let Some(idx) = closest_idx {
+                *areas.entry(idx).or_insert(0) += 1;
+                
+                // If on the boundary, this area is infinite
+                if bounds.on_boundary(&current) {
+                    infinite_areas.insert(idx);
                 }
             }
         }
     }
     
+    debug!(
+        "bounding box {} to {}; {} of {} areas are infinite",
+        bounds.min,
+        bounds.max,
+        infinite_areas.len(),
+        points.len()
+    );
+
     // Find the largest finite area
     areas
         .iter()
@@ -92,30 +110,21 @@ fn part1(input: &str) -> usize {
         .unwrap_or(0)
 }
 
-fn part2(input: &str, max_distance: i32) -> usize {
-    let points = parse_input(input);
-    
+fn safe_region_size(points: &[Point2], max_distance: i64) -> usize {
     // Find the bounding box (expand a bit to be safe)
-    let min_x = points.iter().map(|p| p.x).min().unwrap() - 100;
-    let max_x = points.iter().map(|p| p.x).max().unwrap() + 100;
-    let min_y = points.iter().map(|p| p.y).min().unwrap() - 100;
-    let max_y = points.iter().map(|p| p.y).max().unwrap() + 100;
+    let bounds = BoundingBox::from_points(points.iter().copied()).unwrap().padded(100);
     
     let mut safe_region_size = 0;
     
-    for x in min_x..=max_x {
-        for y in min_y..=max_y {
-            let current = Point { x, y };
-            
-            // Calculate total distance to all points
-            let total_distance: i32 = points
-                .iter()
-                .map(|point| current.manhattan_distance(point))
-                .sum();
-            
-            if total_distance < max_distance {
-                safe_region_size += 1;
-            }
+    for current in bounds.points() {
+        // Calculate total distance to all points
+        let total_distance: i64 = points
+            .iter()
+            .map(|point| current.manhattan(point))
+            .sum();
+        
+        if total_distance < max_distance {
+            safe_region_size += 1;
         }
     }
     
@@ -126,20 +135,19 @@ fn part2(input: &str, max_distance: i32) -> usize {
 mod tests {
     use super::*;
 
-    const TEST_INPUT: &str = "1, 1
-1, 6
-8, 3
-3, 4
-5, 5
-8, 9";
-
     #[test]
     fn test_part1() {
-        assert_eq!(part1(TEST_INPUT), 17);
+        assert_eq!(part1(&parse_input(EXAMPLES[0]).unwrap()), 17);
     }
 
     #[test]
     fn test_part2() {
-        assert_eq!(part2(TEST_INPUT, 32), 16);
+        assert_eq!(safe_region_size(&parse_input(EXAMPLES[0]).unwrap(), 32), 16);
+    }
+
+    #[test]
+    fn test_empty_input() {
+        let err = parse_input("\n").unwrap_err();
+        assert!(err.to_string().contains("no coordinates"));
     }
 }
@@ -1,28 +1,64 @@
-use aoc2018::read_input;
+use crate::error::{parse_at, Result};
+use crate::parse::captures;
+use crate::{debug, trace, Answer, Solution};
 use std::collections::{HashMap, HashSet};
 
-fn parse_input(input: &str) -> (HashSet<char>, HashMap<char, Vec<char>>) {
+type Steps = (HashSet<char>, HashMap<char, Vec<char>>);
+
+pub struct Day07;
+
+/// The examples from the puzzle description, for `aoc run --example N`
+const EXAMPLES: &[&str] = &[
+    "\
+Step C must be finished before step A can begin.
+Step C must be finished before step F can begin.
+Step A must be finished before step B can begin.
+Step A must be finished before step D can begin.
+Step B must be finished before step E can begin.
+Step D must be finished before step E can begin.
+Step F must be finished before step E can begin.",
+];
+
+impl Solution for Day07 {
+    type Input = Steps;
+
+    fn parse(&self, input: &str) -> Result<Steps> {
+        parse_input(input)
+    }
+
+    fn part1(&self, steps: &Steps) -> Answer {
+        part1(steps).into()
+    }
+
+    fn part2(&self, steps: &Steps) -> Answer {
+        assembly_time(steps, 5, 60).into()
+    }
+
+    fn examples(&self) -> &'static [&'static str] {
+        EXAMPLES
+    }
+}
+
+fn parse_input(input: &str) -> Result<Steps> {
     let mut dependencies: HashMap<char, Vec<char>> = HashMap::new();
     let mut all_steps: HashSet<char> = HashSet::new();
 
-    for line in input.lines() {
-        let words: Vec<&str> = line.split_whitespace().collect();
-        if words.len() >= 10 {
-            let prerequisite = words[1].chars().next().unwrap();
-            let step = words[7].chars().next().unwrap();
-            
-            all_steps.insert(prerequisite);
-            all_steps.insert(step);
-            
-            dependencies.entry(step).or_insert_with(Vec::new).push(prerequisite);
-        }
+    for line in input.lines().filter(|line| !line.trim().is_empty()) {
+        let [prerequisite, step] = captures(input, line, "Step {} must be finished before step {} can begin.")?;
+        let prerequisite: char = parse_at(input, prerequisite)?;
+        let step: char = parse_at(input, step)?;
+
+        all_steps.insert(prerequisite);
+        all_steps.insert(step);
+
+        dependencies.entry(step).or_default().push(prerequisite);
     }
 
-    (all_steps, dependencies)
+    Ok((all_steps, dependencies))
 }
 
-fn part1(input: &str) -> String {
-    let (mut all_steps, mut dependencies) = parse_input(input);
+fn part1(steps: &Steps) -> String {
+    let (mut all_steps, mut dependencies) = steps.clone();
     let mut result = String::new();
 
     while !all_steps.is_empty() {
@@ -38,6 +74,7 @@ fn part1(input: &str) -> String {
         // Sort alphabetically and take the first one
         available.sort();
         let next_step = available[0];
+        trace!("available {:?}, taking {}", available, next_step);
 
         // Add to result and remove from available steps
         result.push(next_step);
@@ -52,8 +89,8 @@ fn part1(input: &str) -> String {
     result
 }
 
-fn part2(input: &str, num_workers: usize, base_time: u32) -> u32 {
-    let (all_steps, mut dependencies) = parse_input(input);
+fn assembly_time(steps: &Steps, num_workers: usize, base_time: u32) -> u32 {
+    let (all_steps, mut dependencies) = steps.clone();
     let mut completed: HashSet<char> = HashSet::new();
     let mut in_progress: HashMap<char, u32> = HashMap::new(); // step -> time remaining
     let mut time = 0;
@@ -97,6 +134,7 @@ fn part2(input: &str, num_workers: usize, base_time: u32) -> u32 {
         // Assign work to available workers
         for step in available.iter().take(num_workers - in_progress.len()) {
             let step_time = base_time + (*step as u32 - 'A' as u32 + 1);
+            debug!("second {}: starting {}, done in {}s", time, step, step_time);
             in_progress.insert(*step, step_time);
         }
 
@@ -113,33 +151,17 @@ fn part2(input: &str, num_workers: usize, base_time: u32) -> u32 {
     time
 }
 
-fn main() {
-    let input = read_input(7);
-
-    println!("Part 1: {}", part1(&input));
-    println!("Part 2: {}", part2(&input, 5, 60));
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
 
-    const TEST_INPUT: &str = "\
-Step C must be finished before step A can begin.
-Step C must be finished before step F can begin.
-Step A must be finished before step B can begin.
-Step A must be finished before step D can begin.
-Step B must be finished before step E can begin.
-Step D must be finished before step E can begin.
-Step F must be finished before step E can begin.";
-
     #[test]
     fn test_part1() {
-        assert_eq!(part1(TEST_INPUT), "CABDFE");
+        assert_eq!(part1(&parse_input(EXAMPLES[0]).unwrap()), "CABDFE");
     }
 
     #[test]
     fn test_part2() {
-        assert_eq!(part2(TEST_INPUT, 2, 0), 15);
+        assert_eq!(assembly_time(&parse_input(EXAMPLES[0]).unwrap(), 2, 0), 15);
     }
 }
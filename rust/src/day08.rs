@@ -1,29 +1,64 @@
-use aoc2018::read_input;
+use crate::error::{parse_at, AocError, Result};
+use crate::{trace, Answer, Solution};
+
+pub struct Day08;
+
+/// The examples from the puzzle description, for `aoc run --example N`
+const EXAMPLES: &[&str] = &[
+    "2 3 0 3 10 11 12 1 1 0 1 99 2 1 1 2",
+];
+
+impl Solution for Day08 {
+    type Input = Node;
+
+    fn parse(&self, input: &str) -> Result<Node> {
+        let numbers = parse_input(input)?;
+        let mut index = 0;
+        let root = Node::parse(&numbers, &mut index)
+            .ok_or_else(|| AocError::at(input, &input[input.len()..], "input ends in the middle of a node"))?;
+        if let Some(trailing) = input.split_whitespace().nth(index) {
+            return Err(AocError::at(input, trailing, "trailing numbers after the root node"));
+        }
+        Ok(root)
+    }
+
+    fn part1(&self, root: &Node) -> Answer {
+        root.sum_metadata().into()
+    }
+
+    fn part2(&self, root: &Node) -> Answer {
+        root.value().into()
+    }
 
-struct Node {
+    fn examples(&self) -> &'static [&'static str] {
+        EXAMPLES
+    }
+}
+
+pub struct Node {
     children: Vec<Node>,
     metadata: Vec<i32>,
 }
 
 impl Node {
-    fn parse(numbers: &[i32], index: &mut usize) -> Self {
-        let num_children = numbers[*index];
+    fn parse(numbers: &[i32], index: &mut usize) -> Option<Self> {
+        let num_children = *numbers.get(*index)?;
         *index += 1;
-        let num_metadata = numbers[*index];
+        let num_metadata = *numbers.get(*index)?;
         *index += 1;
 
         let mut children = Vec::new();
         for _ in 0..num_children {
-            children.push(Node::parse(numbers, index));
+            children.push(Node::parse(numbers, index)?);
         }
 
         let mut metadata = Vec::new();
         for _ in 0..num_metadata {
-            metadata.push(numbers[*index]);
+            metadata.push(*numbers.get(*index)?);
             *index += 1;
         }
 
-        Node { children, metadata }
+        Some(Node { children, metadata })
     }
 
     fn sum_metadata(&self) -> i32 {
@@ -46,53 +81,35 @@ impl Node {
                     sum += self.children[(index - 1) as usize].value();
                 }
             }
+            trace!("node with {} children and metadata {:?} is worth {}", self.children.len(), self.metadata, sum);
             sum
         }
     }
 }
 
-fn parse_input(input: &str) -> Vec<i32> {
+fn parse_input(input: &str) -> Result<Vec<i32>> {
     input
-        .trim()
         .split_whitespace()
-        .map(|s| s.parse().unwrap())
+        .map(|s| parse_at(input, s))
         .collect()
 }
 
-fn part1(input: &str) -> i32 {
-    let numbers = parse_input(input);
-    let mut index = 0;
-    let root = Node::parse(&numbers, &mut index);
-    root.sum_metadata()
-}
-
-fn part2(input: &str) -> i32 {
-    let numbers = parse_input(input);
-    let mut index = 0;
-    let root = Node::parse(&numbers, &mut index);
-    root.value()
-}
-
-fn main() {
-    let input = read_input(8);
-
-    println!("Part 1: {}", part1(&input));
-    println!("Part 2: {}", part2(&input));
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
 
-    const TEST_INPUT: &str = "2 3 0 3 10 11 12 1 1 0 1 99 2 1 1 2";
-
     #[test]
     fn test_part1() {
-        assert_eq!(part1(TEST_INPUT), 138);
+        assert_eq!(Day08.parse(EXAMPLES[0]).unwrap().sum_metadata(), 138);
     }
 
     #[test]
     fn test_part2() {
-        assert_eq!(part2(TEST_INPUT), 66);
+        assert_eq!(Day08.parse(EXAMPLES[0]).unwrap().value(), 66);
+    }
+
+    #[test]
+    fn test_truncated_input() {
+        assert!(Day08.parse("2 3 0 3 10 11 12").is_err());
     }
 }
@@ -1,11 +1,44 @@
-use aoc2018::read_input;
+use crate::error::{parse_at, Result};
+use crate::parse::captures;
+use crate::{debug, trace, Answer, Solution};
 use std::collections::VecDeque;
 
-fn parse_input(input: &str) -> (usize, usize) {
-    let parts: Vec<&str> = input.trim().split_whitespace().collect();
-    let players = parts[0].parse().unwrap();
-    let last_marble = parts[6].parse().unwrap();
-    (players, last_marble)
+pub struct Day09;
+
+/// The examples from the puzzle description, for `aoc run --example N`
+const EXAMPLES: &[&str] = &[
+    "9 players; last marble is worth 25 points",
+    "10 players; last marble is worth 1618 points",
+    "13 players; last marble is worth 7999 points",
+    "17 players; last marble is worth 1104 points",
+    "21 players; last marble is worth 6111 points",
+    "30 players; last marble is worth 5807 points",
+];
+
+impl Solution for Day09 {
+    type Input = (usize, usize);
+
+    fn parse(&self, input: &str) -> Result<(usize, usize)> {
+        parse_input(input)
+    }
+
+    fn part1(&self, &(players, last_marble): &(usize, usize)) -> Answer {
+        play_game(players, last_marble).into()
+    }
+
+    fn part2(&self, &(players, last_marble): &(usize, usize)) -> Answer {
+        play_game(players, last_marble * 100).into()
+    }
+
+    fn examples(&self) -> &'static [&'static str] {
+        EXAMPLES
+    }
+}
+
+fn parse_input(input: &str) -> Result<(usize, usize)> {
+    let line = input.lines().next().unwrap_or(input);
+    let [players, last_marble] = captures(input, line, "{} players; last marble is worth {} points")?;
+    Ok((parse_at(input, players)?, parse_at(input, last_marble)?))
 }
 
 fn play_game(players: usize, last_marble: usize) -> usize {
@@ -28,6 +61,7 @@ fn play_game(players: usize, last_marble: usize) -> usize {
             // Remove and score the current marble
             let removed = circle.pop_front().unwrap();
             scores[player] += removed;
+            trace!("player {} keeps {} and takes {}, now at {}", player + 1, marble, removed, scores[player]);
             
             // Next marble becomes current (it's already at front)
         } else {
@@ -40,24 +74,9 @@ fn play_game(players: usize, last_marble: usize) -> usize {
         }
     }
     
-    *scores.iter().max().unwrap()
-}
-
-fn part1(input: &str) -> usize {
-    let (players, last_marble) = parse_input(input);
-    play_game(players, last_marble)
-}
-
-fn part2(input: &str) -> usize {
-    let (players, last_marble) = parse_input(input);
-    play_game(players, last_marble * 100)
-}
-
-fn main() {
-    let input = read_input(9);
-
-    println!("Part 1: {}", part1(&input));
-    println!("Part 2: {}", part2(&input));
+    let winner = (0..players).max_by_key(|&player| scores[player]).unwrap();
+    debug!("player {} wins with {} points", winner + 1, scores[winner]);
+    scores[winner]
 }
 
 #[cfg(test)]
@@ -66,11 +85,10 @@ mod tests {
 
     #[test]
     fn test_part1_examples() {
-        assert_eq!(play_game(9, 25), 32);
-        assert_eq!(play_game(10, 1618), 8317);
-        assert_eq!(play_game(13, 7999), 146373);
-        assert_eq!(play_game(17, 1104), 2764);
-        assert_eq!(play_game(21, 6111), 54718);
-        assert_eq!(play_game(30, 5807), 37305);
+        let expected = [32, 8317, 146373, 2764, 54718, 37305];
+        for (example, score) in EXAMPLES.iter().zip(expected) {
+            let (players, last_marble) = parse_input(example).unwrap();
+            assert_eq!(play_game(players, last_marble), score, "{}", example);
+        }
     }
 }
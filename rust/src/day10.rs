@@ -1,76 +1,125 @@
-use aoc2018::read_input;
+use crate::error::Result;
+use crate::geom::{BoundingBox, Point2};
+use crate::parse::exact_ints;
+use crate::{debug, trace, Answer, Solution};
 use std::collections::HashSet;
 
+pub struct Day10;
+
+/// The examples from the puzzle description, for `aoc run --example N`
+const EXAMPLES: &[&str] = &[
+    "\
+position=< 9,  1> velocity=< 0,  2>
+position=< 7,  0> velocity=<-1,  0>
+position=< 3, -2> velocity=<-1,  1>
+position=< 6, 10> velocity=<-2, -1>
+position=< 2, -4> velocity=< 2,  2>
+position=<-6, 10> velocity=< 2, -2>
+position=< 1,  8> velocity=< 1, -1>
+position=< 1,  7> velocity=< 1,  0>
+position=<-3, 11> velocity=< 1, -2>
+position=< 7,  6> velocity=<-1, -1>
+position=<-2,  3> velocity=< 1,  0>
+position=<-4,  3> velocity=< 2,  0>
+position=<10, -3> velocity=<-1,  1>
+position=< 5, 11> velocity=< 1, -2>
+position=< 4,  7> velocity=< 0, -1>
+position=< 8, -2> velocity=< 0,  1>
+position=<15,  0> velocity=<-2,  0>
+position=< 1,  6> velocity=< 1,  0>
+position=< 8,  9> velocity=< 0, -1>
+position=< 3,  3> velocity=<-1,  1>
+position=< 0,  5> velocity=< 0, -1>
+position=<-2,  2> velocity=< 2,  0>
+position=< 5, -2> velocity=< 1,  2>
+position=< 1,  4> velocity=< 2,  1>
+position=<-2,  7> velocity=< 2, -2>
+position=< 3,  6> velocity=<-1, -1>
+position=< 5,  0> velocity=< 1,  0>
+position=<-6,  0> velocity=< 2,  0>
+position=< 5,  9> velocity=< 1, -2>
+position=<14,  7> velocity=<-2,  0>
+position=<-3,  6> velocity=< 2, -1>",
+];
+
+impl Solution for Day10 {
+    type Input = Vec<Light>;
+
+    fn parse(&self, input: &str) -> Result<Vec<Light>> {
+        parse_input(input)
+    }
+
+    fn part1(&self, points: &Vec<Light>) -> Answer {
+        Answer::grid(&solve(points).0)
+    }
+
+    fn part2(&self, points: &Vec<Light>) -> Answer {
+        solve(points).1.into()
+    }
+
+    fn examples(&self) -> &'static [&'static str] {
+        EXAMPLES
+    }
+}
+
 #[derive(Debug, Clone, Copy)]
-struct Point {
-    x: i32,
-    y: i32,
-    vx: i32,
-    vy: i32,
+pub struct Light {
+    position: Point2,
+    velocity: Point2,
 }
 
-impl Point {
+impl Light {
     fn step(&mut self) {
-        self.x += self.vx;
-        self.y += self.vy;
+        self.position += self.velocity;
     }
 
     fn step_back(&mut self) {
-        self.x -= self.vx;
-        self.y -= self.vy;
+        self.position -= self.velocity;
     }
 }
 
-fn parse_input(input: &str) -> Vec<Point> {
+fn parse_input(input: &str) -> Result<Vec<Light>> {
     input
         .lines()
         .filter(|line| !line.is_empty())
         .map(|line| {
-            // position=< 10775, -31651> velocity=<-1,  3>
-            let parts: Vec<&str> = line.split(&['<', '>', ','][..]).collect();
-            Point {
-                x: parts[1].trim().parse().unwrap(),
-                y: parts[2].trim().parse().unwrap(),
-                vx: parts[4].trim().parse().unwrap(),
-                vy: parts[5].trim().parse().unwrap(),
-            }
+            let [x, y, vx, vy] = exact_ints(input, line)?;
+            Ok(Light {
+                position: Point2::new([x, y]),
+                velocity: Point2::new([vx, vy]),
+            })
         })
         .collect()
 }
 
-fn bounding_box(points: &[Point]) -> (i32, i32, i32, i32) {
-    let min_x = points.iter().map(|p| p.x).min().unwrap();
-    let max_x = points.iter().map(|p| p.x).max().unwrap();
-    let min_y = points.iter().map(|p| p.y).min().unwrap();
-    let max_y = points.iter().map(|p| p.y).max().unwrap();
-    (min_x, max_x, min_y, max_y)
+fn bounding_box(points: &[Light]) -> BoundingBox<2> {
+    BoundingBox::from_points(points.iter().map(|p| p.position)).unwrap()
 }
 
-fn area_size(points: &[Point]) -> i64 {
-    let (min_x, max_x, min_y, max_y) = bounding_box(points);
-    (max_x - min_x) as i64 * (max_y - min_y) as i64
+fn area_size(points: &[Light]) -> i64 {
+    bounding_box(points).area()
 }
 
-fn display_points(points: &[Point]) -> String {
-    let (min_x, max_x, min_y, max_y) = bounding_box(points);
-    let point_set: HashSet<(i32, i32)> = points.iter().map(|p| (p.x, p.y)).collect();
+fn display_points(points: &[Light]) -> String {
+    let bounds = bounding_box(points);
+    let point_set: HashSet<Point2> = points.iter().map(|p| p.position).collect();
     
     let mut result = String::new();
-    for y in min_y..=max_y {
-        for x in min_x..=max_x {
-            if point_set.contains(&(x, y)) {
-                result.push('#');
-            } else {
-                result.push('.');
-            }
+    for position in bounds.points() {
+        if point_set.contains(&position) {
+            result.push('#');
+        } else {
+            result.push('.');
+        }
+        if position[0] == bounds.max[0] {
+            result.push('\n');
         }
-        result.push('\n');
     }
     result
 }
 
-fn solve(input: &str) -> (String, i32) {
-    let mut points = parse_input(input);
+fn solve(points: &[Light]) -> (String, i32) {
+    let mut points = points.to_vec();
     let mut seconds = 0;
     let mut prev_area = area_size(&points);
     
@@ -82,12 +131,14 @@ fn solve(input: &str) -> (String, i32) {
         seconds += 1;
         
         let current_area = area_size(&points);
+        trace!("second {}: bounding box area {}", seconds, current_area);
         if current_area > prev_area {
             // We've gone too far, step back one
             for point in points.iter_mut() {
                 point.step_back();
             }
             seconds -= 1;
+            debug!("area grows again after second {}, the message is at {} with area {}", seconds + 1, seconds, prev_area);
             break;
         }
         prev_area = current_area;
@@ -96,67 +147,18 @@ fn solve(input: &str) -> (String, i32) {
     (display_points(&points), seconds)
 }
 
-fn part1(input: &str) -> String {
-    solve(input).0
-}
-
-fn part2(input: &str) -> i32 {
-    solve(input).1
-}
-
-fn main() {
-    let input = read_input(10);
-
-    let (message, seconds) = solve(&input);
-    println!("Part 1:\n{}", message);
-    println!("Part 2: {}", seconds);
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
 
-    const TEST_INPUT: &str = "\
-position=< 9,  1> velocity=< 0,  2>
-position=< 7,  0> velocity=<-1,  0>
-position=< 3, -2> velocity=<-1,  1>
-position=< 6, 10> velocity=<-2, -1>
-position=< 2, -4> velocity=< 2,  2>
-position=<-6, 10> velocity=< 2, -2>
-position=< 1,  8> velocity=< 1, -1>
-position=< 1,  7> velocity=< 1,  0>
-position=<-3, 11> velocity=< 1, -2>
-position=< 7,  6> velocity=<-1, -1>
-position=<-2,  3> velocity=< 1,  0>
-position=<-4,  3> velocity=< 2,  0>
-position=<10, -3> velocity=<-1,  1>
-position=< 5, 11> velocity=< 1, -2>
-position=< 4,  7> velocity=< 0, -1>
-position=< 8, -2> velocity=< 0,  1>
-position=<15,  0> velocity=<-2,  0>
-position=< 1,  6> velocity=< 1,  0>
-position=< 8,  9> velocity=< 0, -1>
-position=< 3,  3> velocity=<-1,  1>
-position=< 0,  5> velocity=< 0, -1>
-position=<-2,  2> velocity=< 2,  0>
-position=< 5, -2> velocity=< 1,  2>
-position=< 1,  4> velocity=< 2,  1>
-position=<-2,  7> velocity=< 2, -2>
-position=< 3,  6> velocity=<-1, -1>
-position=< 5,  0> velocity=< 1,  0>
-position=<-6,  0> velocity=< 2,  0>
-position=< 5,  9> velocity=< 1, -2>
-position=<14,  7> velocity=<-2,  0>
-position=<-3,  6> velocity=< 2, -1>";
-
     #[test]
     fn test_part2() {
-        assert_eq!(part2(TEST_INPUT), 3);
+        assert_eq!(solve(&parse_input(EXAMPLES[0]).unwrap()).1, 3);
     }
     
     #[test]
     fn test_display() {
-        let message = part1(TEST_INPUT);
+        let (message, _) = solve(&parse_input(EXAMPLES[0]).unwrap());
         println!("{}", message);
         // Should spell "HI"
         assert!(message.contains("#...#..###"));
@@ -1,4 +1,30 @@
-use aoc2018::read_input;
+use crate::error::{parse_at, Result};
+use crate::{debug, Answer, Solution};
+
+pub struct Day11;
+
+/// The examples from the puzzle description, for `aoc run --example N`
+const EXAMPLES: &[&str] = &["18", "42"];
+
+impl Solution for Day11 {
+    type Input = i32;
+
+    fn parse(&self, input: &str) -> Result<i32> {
+        parse_at(input, input.trim())
+    }
+
+    fn part1(&self, &serial: &i32) -> Answer {
+        part1(serial).into()
+    }
+
+    fn part2(&self, &serial: &i32) -> Answer {
+        part2(serial).into()
+    }
+
+    fn examples(&self) -> &'static [&'static str] {
+        EXAMPLES
+    }
+}
 
 fn power_level(x: i32, y: i32, serial: i32) -> i32 {
     let rack_id = x + 10;
@@ -27,7 +53,7 @@ fn build_sat(serial: i32) -> Vec<Vec<i32>> {
 }
 
 // Get sum of square with top-left at (x, y) and size `size`
-fn square_sum(sat: &Vec<Vec<i32>>, x: i32, y: i32, size: i32) -> i32 {
+fn square_sum(sat: &[Vec<i32>], x: i32, y: i32, size: i32) -> i32 {
     let x1 = x - 1;
     let y1 = y - 1;
     let x2 = x + size - 1;
@@ -39,8 +65,7 @@ fn square_sum(sat: &Vec<Vec<i32>>, x: i32, y: i32, size: i32) -> i32 {
         + sat[y1 as usize][x1 as usize]
 }
 
-fn part1(input: &str) -> String {
-    let serial: i32 = input.trim().parse().unwrap();
+fn part1(serial: i32) -> String {
     let sat = build_sat(serial);
     
     let mut max_power = i32::MIN;
@@ -61,8 +86,7 @@ fn part1(input: &str) -> String {
     format!("{},{}", best_x, best_y)
 }
 
-fn part2(input: &str) -> String {
-    let serial: i32 = input.trim().parse().unwrap();
+fn part2(serial: i32) -> String {
     let sat = build_sat(serial);
     
     let mut max_power = i32::MIN;
@@ -79,6 +103,7 @@ fn part2(input: &str) -> String {
                     best_x = x;
                     best_y = y;
                     best_size = size;
+                    debug!("new best square {},{},{} with power {}", x, y, size, power);
                 }
             }
         }
@@ -87,13 +112,6 @@ fn part2(input: &str) -> String {
     format!("{},{},{}", best_x, best_y, best_size)
 }
 
-fn main() {
-    let input = read_input(11);
-
-    println!("Part 1: {}", part1(&input));
-    println!("Part 2: {}", part2(&input));
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -108,13 +126,13 @@ mod tests {
 
     #[test]
     fn test_part1() {
-        assert_eq!(part1("18"), "33,45");
-        assert_eq!(part1("42"), "21,61");
+        assert_eq!(part1(Day11.parse(EXAMPLES[0]).unwrap()), "33,45");
+        assert_eq!(part1(Day11.parse(EXAMPLES[1]).unwrap()), "21,61");
     }
     
     #[test]
     fn test_part2() {
-        assert_eq!(part2("18"), "90,269,16");
-        assert_eq!(part2("42"), "232,251,12");
+        assert_eq!(part2(Day11.parse(EXAMPLES[0]).unwrap()), "90,269,16");
+        assert_eq!(part2(Day11.parse(EXAMPLES[1]).unwrap()), "232,251,12");
     }
 }
@@ -1,15 +1,61 @@
-use aoc2018::read_input;
+use crate::cycle;
+use crate::error::{AocError, Result};
+use crate::{debug, Answer, Solution};
 use std::collections::HashSet;
 
 type Pots = HashSet<i64>;
+type Rules = HashSet<[u8; 5]>;
 
-fn parse_input(input: &str) -> (Pots, HashSet<[u8; 5]>) {
+pub struct Day12;
+
+/// The examples from the puzzle description, for `aoc run --example N`
+const EXAMPLES: &[&str] = &[
+    "\
+initial state: #..#.#..##......###...###
+
+...## => #
+..#.. => #
+.#... => #
+.#.#. => #
+.#.## => #
+.##.. => #
+.#### => #
+#.#.# => #
+#.### => #
+##.#. => #
+##.## => #
+###.. => #
+###.# => #
+####. => #",
+];
+
+impl Solution for Day12 {
+    type Input = (Pots, Rules);
+
+    fn parse(&self, input: &str) -> Result<(Pots, Rules)> {
+        parse_input(input)
+    }
+
+    fn part1(&self, (plants, rules): &(Pots, Rules)) -> Answer {
+        part1(plants, rules).into()
+    }
+
+    fn part2(&self, (plants, rules): &(Pots, Rules)) -> Answer {
+        part2(plants, rules).into()
+    }
+
+    fn examples(&self) -> &'static [&'static str] {
+        EXAMPLES
+    }
+}
+
+fn parse_input(input: &str) -> Result<(Pots, Rules)> {
     let mut lines = input.lines();
 
-    let initial_line = lines.next().unwrap();
+    let initial_line = lines.next().unwrap_or(input);
     let initial_state = initial_line
         .strip_prefix("initial state: ")
-        .unwrap()
+        .ok_or_else(|| AocError::at(input, initial_line, "expected `initial state: ...`"))?
         .trim();
 
     let mut plants: Pots = HashSet::new();
@@ -27,7 +73,10 @@ fn parse_input(input: &str) -> (Pots, HashSet<[u8; 5]>) {
         if line.trim().is_empty() {
             continue;
         }
-        let (pat, res) = line.split_once(" => ").unwrap();
+        let (pat, res) = line
+            .split_once(" => ")
+            .filter(|(pat, res)| pat.len() == 5 && !res.is_empty())
+            .ok_or_else(|| AocError::at(input, line, "expected a rule like `..#.. => #`"))?;
         if res.as_bytes()[0] == b'#' {
             let mut arr = [b'.'; 5];
             for (i, c) in pat.bytes().enumerate() {
@@ -37,10 +86,10 @@ fn parse_input(input: &str) -> (Pots, HashSet<[u8; 5]>) {
         }
     }
 
-    (plants, rules)
+    Ok((plants, rules))
 }
 
-fn next_generation(current: &Pots, rules: &HashSet<[u8; 5]>) -> Pots {
+fn next_generation(current: &Pots, rules: &Rules) -> Pots {
     if current.is_empty() {
         return Pots::new();
     }
@@ -71,89 +120,70 @@ fn sum_of_plants(plants: &Pots) -> i64 {
     plants.iter().sum()
 }
 
-fn part1(input: &str) -> i64 {
-    let (mut plants, rules) = parse_input(input);
+fn part1(plants: &Pots, rules: &Rules) -> i64 {
+    let mut plants = plants.clone();
 
     for _ in 0..20 {
-        plants = next_generation(&plants, &rules);
+        plants = next_generation(&plants, rules);
     }
 
     sum_of_plants(&plants)
 }
 
-fn part2(input: &str) -> i64 {
-    let target: i64 = 50_000_000_000;
-    let (mut plants, rules) = parse_input(input);
-
-    let mut last_sum = sum_of_plants(&plants);
-    let mut last_delta = 0;
-    let mut stable_steps = 0;
-
-    let mut generation: i64 = 0;
+/// The plants relative to the leftmost one, which repeats once the pattern
+/// just moves along
+fn shape(plants: &Pots) -> Vec<i64> {
+    let min = plants.iter().min().copied().unwrap_or(0);
+    let mut shape: Vec<i64> = plants.iter().map(|pot| pot - min).collect();
+    shape.sort_unstable();
+    shape
+}
 
-    while generation < target {
-        generation += 1;
-        plants = next_generation(&plants, &rules);
-        let current_sum = sum_of_plants(&plants);
-        let delta = current_sum - last_sum;
+fn part2(plants: &Pots, rules: &Rules) -> i64 {
+    let target = 50_000_000_000;
+    let step = |plants: &Pots| next_generation(plants, rules);
 
-        if delta == last_delta {
-            stable_steps += 1;
-        } else {
-            stable_steps = 0;
-            last_delta = delta;
-        }
+    let cycle = cycle::find_cycle(plants.clone(), step, shape);
+    let generation = cycle.equivalent_step(target);
 
-        // Heuristic: once the delta has been stable for 200 generations,
-        // assume linear growth and fast-forward the remaining steps.
-        if stable_steps >= 200 {
-            let remaining = target - generation;
-            return current_sum + remaining * delta;
-        }
-
-        last_sum = current_sum;
+    // Simulate to the generation with the target's shape, then one more cycle
+    // to see how far the plants move per cycle
+    let mut plants = plants.clone();
+    for _ in 0..generation {
+        plants = step(&plants);
     }
-
-    last_sum
-}
-
-fn main() {
-    let input = read_input(12);
-
-    println!("Part 1: {}", part1(&input));
-    println!("Part 2: {}", part2(&input));
+    let mut next = plants.clone();
+    for _ in 0..cycle.length {
+        next = step(&next);
+    }
+    let shift = next.iter().min().unwrap_or(&0) - plants.iter().min().unwrap_or(&0);
+    let cycles = cycle.full_cycles(target) as i64;
+    debug!(
+        "generation {} has the shape of generation {}, moving {} pots every {} generations",
+        cycle.start + cycle.length,
+        cycle.start,
+        shift,
+        cycle.length
+    );
+
+    sum_of_plants(&plants) + cycles * shift * plants.len() as i64
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
-    const TEST_INPUT: &str = "\
-initial state: #..#.#..##......###...###
-
-...## => #
-..#.. => #
-.#... => #
-.#.#. => #
-.#.## => #
-.##.. => #
-.#### => #
-#.#.# => #
-#.### => #
-##.#. => #
-##.## => #
-###.. => #
-###.# => #
-####. => #";
-
     #[test]
     fn test_part1() {
-        assert_eq!(part1(TEST_INPUT), 325);
+        let (plants, rules) = parse_input(EXAMPLES[0]).unwrap();
+        assert_eq!(part1(&plants, &rules), 325);
     }
 
     #[test]
     fn test_part2_convergence() {
-        // The sample does not provide a part 2 answer; ensure the code executes.
-        let _ = part2(TEST_INPUT);
+        // The sample does not provide a part 2 answer; from generation 86 on it
+        // moves one pot to the right per generation
+        let (plants, rules) = parse_input(EXAMPLES[0]).unwrap();
+        assert_eq!(part2(&plants, &rules), 999_999_999_374);
     }
 }
@@ -1,6 +1,50 @@
-use aoc2018::read_input;
+use crate::error::{AocError, Result};
+use crate::{debug, Answer, Solution};
 use std::cmp::Ordering;
 
+type Tracks = Vec<Vec<char>>;
+
+pub struct Day13;
+
+/// The examples from the puzzle description, for `aoc run --example N`
+const EXAMPLES: &[&str] = &[
+    "\
+/->-\\        
+|   |  /----\\
+| /-+--+-\\  |
+| | |  | v  |
+\\-+-/  \\-+--/
+  \\------/   ",
+    "\
+/>-<\\  
+|   |  
+| /<+-\\
+| | | v
+\\>+</ |
+  |   ^
+  \\<->/",
+];
+
+impl Solution for Day13 {
+    type Input = (Tracks, Vec<Cart>);
+
+    fn parse(&self, input: &str) -> Result<(Tracks, Vec<Cart>)> {
+        parse(input)
+    }
+
+    fn part1(&self, (grid, carts): &(Tracks, Vec<Cart>)) -> Answer {
+        part1(grid, carts).into()
+    }
+
+    fn part2(&self, (grid, carts): &(Tracks, Vec<Cart>)) -> Answer {
+        part2(grid, carts).into()
+    }
+
+    fn examples(&self) -> &'static [&'static str] {
+        EXAMPLES
+    }
+}
+
 #[derive(Copy, Clone, Debug, Eq, PartialEq)]
 enum Dir {
     Up,
@@ -30,7 +74,7 @@ impl Dir {
 }
 
 #[derive(Clone, Debug)]
-struct Cart {
+pub struct Cart {
     x: i32,
     y: i32,
     dir: Dir,
@@ -38,7 +82,7 @@ struct Cart {
     alive: bool,
 }
 
-fn parse(input: &str) -> (Vec<Vec<char>>, Vec<Cart>) {
+fn parse(input: &str) -> Result<(Tracks, Vec<Cart>)> {
     let mut grid: Vec<Vec<char>> = input.lines().map(|l| l.chars().collect()).collect();
     let mut carts = Vec::new();
 
@@ -49,7 +93,14 @@ fn parse(input: &str) -> (Vec<Vec<char>>, Vec<Cart>) {
                 'v' => Some(Dir::Down),
                 '<' => Some(Dir::Left),
                 '>' => Some(Dir::Right),
-                _ => None,
+                ' ' | '-' | '|' | '/' | '\\' | '+' => None,
+                other => {
+                    return Err(AocError::Parse {
+                        line: y + 1,
+                        column: x + 1,
+                        message: format!("unexpected track character {:?}", other),
+                    })
+                }
             };
 
             if let Some(d) = dir {
@@ -69,7 +120,7 @@ fn parse(input: &str) -> (Vec<Vec<char>>, Vec<Cart>) {
         }
     }
 
-    (grid, carts)
+    Ok((grid, carts))
 }
 
 fn step_cart(cart: &mut Cart, grid: &[Vec<char>]) {
@@ -111,7 +162,9 @@ fn step_cart(cart: &mut Cart, grid: &[Vec<char>]) {
     }
 }
 
-fn simulate(grid: &[Vec<char>], mut carts: Vec<Cart>, stop_on_first_collision: bool) -> (Option<(i32, i32)>, Option<(i32, i32)>) {
+type Position = (i32, i32);
+
+fn simulate(grid: &[Vec<char>], mut carts: Vec<Cart>, stop_on_first_collision: bool) -> (Option<Position>, Option<Position>) {
     let mut first_collision = None;
 
     loop {
@@ -132,6 +185,7 @@ fn simulate(grid: &[Vec<char>], mut carts: Vec<Cart>, stop_on_first_collision: b
                     continue;
                 }
                 if carts[i].x == carts[j].x && carts[i].y == carts[j].y {
+                    debug!("carts crash at {},{}", carts[i].x, carts[i].y);
                     if first_collision.is_none() {
                         first_collision = Some((carts[i].x, carts[i].y));
                     }
@@ -153,54 +207,31 @@ fn simulate(grid: &[Vec<char>], mut carts: Vec<Cart>, stop_on_first_collision: b
     }
 }
 
-fn part1(input: &str) -> String {
-    let (grid, carts) = parse(input);
-    let (first, _) = simulate(&grid, carts, true);
+fn part1(grid: &[Vec<char>], carts: &[Cart]) -> String {
+    let (first, _) = simulate(grid, carts.to_vec(), true);
     let (x, y) = first.expect("No collision found");
     format!("{},{}", x, y)
 }
 
-fn part2(input: &str) -> String {
-    let (grid, carts) = parse(input);
-    let (_, last) = simulate(&grid, carts, false);
+fn part2(grid: &[Vec<char>], carts: &[Cart]) -> String {
+    let (_, last) = simulate(grid, carts.to_vec(), false);
     let (x, y) = last.expect("No cart left");
     format!("{},{}", x, y)
 }
 
-fn main() {
-    let input = read_input(13);
-    println!("Part 1: {}", part1(&input));
-    println!("Part 2: {}", part2(&input));
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
 
-    const SAMPLE1: &str = "\
-/->-\\        
-|   |  /----\\
-| /-+--+-\\  |
-| | |  | v  |
-\\-+-/  \\-+--/
-  \\------/   ";
-
-    const SAMPLE2: &str = "\
-/>-<\\  
-|   |  
-| /<+-\\
-| | | v
-\\>+</ |
-  |   ^
-  \\<->/";
-
     #[test]
     fn test_part1_sample() {
-        assert_eq!(part1(SAMPLE1), "7,3");
+        let (grid, carts) = parse(EXAMPLES[0]).unwrap();
+        assert_eq!(part1(&grid, &carts), "7,3");
     }
 
     #[test]
     fn test_part2_sample() {
-        assert_eq!(part2(SAMPLE2), "6,4");
+        let (grid, carts) = parse(EXAMPLES[1]).unwrap();
+        assert_eq!(part2(&grid, &carts), "6,4");
     }
 }
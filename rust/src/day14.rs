@@ -1,4 +1,40 @@
-use aoc2018::read_input;
+use crate::error::{parse_at, AocError, Result};
+use crate::{debug, Answer, Solution};
+
+pub struct Day14;
+
+/// The examples from the puzzle description, for `aoc run --example N`; the
+/// first four are for part 1 and the rest for part 2
+const EXAMPLES: &[&str] = &["9", "5", "18", "2018", "51589", "01245", "92510", "59414"];
+
+/// The puzzle input as a number of recipes for part 1 and as a sequence of
+/// digits for part 2
+type Puzzle = (usize, Vec<u8>);
+
+impl Solution for Day14 {
+    type Input = Puzzle;
+
+    fn parse(&self, input: &str) -> Result<Puzzle> {
+        let puzzle = input.trim();
+        match puzzle.char_indices().find(|(_, c)| !c.is_ascii_digit()) {
+            Some((i, _)) => Err(AocError::at(input, &puzzle[i..], "expected only digits")),
+            None if puzzle.is_empty() => Err(AocError::at(input, puzzle, "expected a number")),
+            None => Ok((parse_at(input, puzzle)?, puzzle.bytes().map(|b| b - b'0').collect())),
+        }
+    }
+
+    fn part1(&self, &(recipes, _): &Puzzle) -> Answer {
+        part1(recipes).into()
+    }
+
+    fn part2(&self, (_, pattern): &Puzzle) -> Answer {
+        part2(pattern).into()
+    }
+
+    fn examples(&self) -> &'static [&'static str] {
+        EXAMPLES
+    }
+}
 
 fn make_recipes(target_len: usize) -> Vec<u8> {
     let mut recipes: Vec<u8> = vec![3, 7];
@@ -21,18 +57,17 @@ fn make_recipes(target_len: usize) -> Vec<u8> {
     recipes
 }
 
-fn part1(input: &str) -> String {
-    let n: usize = input.trim().parse().unwrap();
+fn part1(n: usize) -> String {
     let target_len = n + 10;
     let recipes = make_recipes(target_len);
+    debug!("made {} recipes to read the ten after {}", recipes.len(), n);
     recipes[n..n + 10]
         .iter()
         .map(|d| (b'0' + *d) as char)
         .collect()
 }
 
-fn part2(input: &str) -> usize {
-    let pattern: Vec<u8> = input.trim().bytes().map(|b| b - b'0').collect();
+fn part2(pattern: &[u8]) -> usize {
     let m = pattern.len();
 
     let mut recipes: Vec<u8> = vec![3, 7];
@@ -53,43 +88,39 @@ fn part2(input: &str) -> usize {
 
         // Check for pattern at the end or one before the end (since we can add two digits)
         let len = recipes.len();
-        if len >= m {
-            if recipes[len - m..len] == pattern {
-                return len - m;
-            }
+        if len >= m && recipes[len - m..len] == *pattern {
+            return len - m;
         }
-        if len >= m + 1 {
-            if recipes[len - m - 1..len - 1] == pattern {
-                return len - m - 1;
-            }
+        if len > m && recipes[len - m - 1..len - 1] == *pattern {
+            return len - m - 1;
         }
     }
 }
 
-fn main() {
-    let input = read_input(14);
-
-    println!("Part 1: {}", part1(&input));
-    println!("Part 2: {}", part2(&input));
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
 
     #[test]
     fn test_part1_samples() {
-        assert_eq!(part1("9"), "5158916779");
-        assert_eq!(part1("5"), "0124515891");
-        assert_eq!(part1("18"), "9251071085");
-        assert_eq!(part1("2018"), "5941429882");
+        assert_eq!(part1(Day14.parse(EXAMPLES[0]).unwrap().0), "5158916779");
+        assert_eq!(part1(Day14.parse(EXAMPLES[1]).unwrap().0), "0124515891");
+        assert_eq!(part1(Day14.parse(EXAMPLES[2]).unwrap().0), "9251071085");
+        assert_eq!(part1(Day14.parse(EXAMPLES[3]).unwrap().0), "5941429882");
     }
 
     #[test]
     fn test_part2_samples() {
-        assert_eq!(part2("51589"), 9);
-        assert_eq!(part2("01245"), 5);
-        assert_eq!(part2("92510"), 18);
-        assert_eq!(part2("59414"), 2018);
+        assert_eq!(part2(&Day14.parse(EXAMPLES[4]).unwrap().1), 9);
+        assert_eq!(part2(&Day14.parse(EXAMPLES[5]).unwrap().1), 5);
+        assert_eq!(part2(&Day14.parse(EXAMPLES[6]).unwrap().1), 18);
+        assert_eq!(part2(&Day14.parse(EXAMPLES[7]).unwrap().1), 2018);
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert!(Day14.parse("").is_err());
+        assert!(Day14.parse("2018x").is_err());
+        assert!(Day14.parse("123456789012345678901234567890").is_err());
     }
 }
@@ -1,5 +1,82 @@
-use aoc2018::read_input;
-use std::collections::{VecDeque, HashMap};
+use crate::error::{AocError, Result};
+use crate::{debug, info, trace, Answer, Solution};
+use crate::search;
+
+pub struct Day15;
+
+/// The examples from the puzzle description, for `aoc run --example N`
+const EXAMPLES: &[&str] = &[
+    "\
+#######
+#.G...#
+#...EG#
+#.#.#G#
+#..G#E#
+#.....#
+#######",
+    "\
+#######
+#G..#E#
+#E#E.E#
+#G.##.#
+#...#E#
+#...E.#
+#######",
+    "\
+#######
+#E..EG#
+#.#G.E#
+#E.##E#
+#G..#.#
+#..E#.#
+#######",
+    "\
+#######
+#E.G#.#
+#.#G..#
+#G.#.G#
+#G..#.#
+#...E.#
+#######",
+    "\
+#######
+#.E...#
+#.#..G#
+#.###.#
+#E#G#G#
+#...#G#
+#######",
+    "\
+#########
+#G......#
+#.E.#...#
+#..##..G#
+#...##..#
+#...#...#
+#.G...G.#
+#.....G.#
+#########",
+];
+
+impl Solution for Day15 {
+    type Input = State;
+
+    fn parse(&self, input: &str) -> Result<State> {
+        State::from_input(input, 3)
+    }
+
+    fn part1(&self, state: &State) -> Answer {
+        part1(state).into()
+    }
+
+    fn part2(&self, state: &State) -> Answer {
+        part2(state).into()
+    }
+
+    fn examples(&self) -> &'static [&'static str] {
+        EXAMPLES
+    }
+}
 
 #[derive(Clone, Copy, Eq, PartialEq, Debug)]
 enum UnitType {
@@ -16,13 +93,13 @@ struct Unit {
 }
 
 #[derive(Clone)]
-struct State {
+pub struct State {
     grid: Vec<Vec<char>>,
     units: Vec<Unit>,
 }
 
 impl State {
-    fn from_input(input: &str, elf_attack: i32) -> Self {
+    fn from_input(input: &str, elf_attack: i32) -> Result<Self> {
         let mut grid: Vec<Vec<char>> = input.lines().map(|l| l.chars().collect()).collect();
         let mut units = Vec::new();
 
@@ -31,7 +108,14 @@ impl State {
                 let unit_type = match *ch {
                     'E' => Some(UnitType::Elf),
                     'G' => Some(UnitType::Goblin),
-                    _ => None,
+                    '#' | '.' => None,
+                    other => {
+                        return Err(AocError::Parse {
+                            line: y + 1,
+                            column: x + 1,
+                            message: format!("unexpected map character {:?}", other),
+                        })
+                    }
                 };
                 if let Some(ut) = unit_type {
                     let attack = match ut {
@@ -49,7 +133,15 @@ impl State {
             }
         }
 
-        State { grid, units }
+        Ok(State { grid, units })
+    }
+
+    fn with_elf_attack(&self, elf_attack: i32) -> Self {
+        let mut state = self.clone();
+        for unit in state.units.iter_mut().filter(|u| u.unit_type == UnitType::Elf) {
+            unit.attack = elf_attack;
+        }
+        state
     }
 
     fn is_occupied(&self, pos: (usize, usize)) -> bool {
@@ -103,28 +195,19 @@ impl State {
             return None;
         }
 
-        // BFS from starting position
-        let mut queue = VecDeque::new();
-        let mut distances = HashMap::new();
-        
-        queue.push_back(from);
-        distances.insert(from, 0);
-
-        while let Some(pos) = queue.pop_front() {
-            let dist = distances[&pos];
+        let walkable = |&pos: &(usize, usize)| {
+            self.neighbors(pos)
+                .into_iter()
+                .filter(|&neighbor| self.is_open(neighbor) || neighbor == from)
+        };
 
-            for neighbor in self.neighbors(pos) {
-                if (self.is_open(neighbor) || neighbor == from) && !distances.contains_key(&neighbor) {
-                    distances.insert(neighbor, dist + 1);
-                    queue.push_back(neighbor);
-                }
-            }
-        }
+        // BFS from starting position
+        let paths = search::bfs(from, walkable, |_| false);
 
         // Find reachable in-range positions
         let mut reachable: Vec<((usize, usize), usize)> = in_range
             .iter()
-            .filter_map(|&pos| distances.get(&pos).map(|&d| (pos, d)))
+            .filter_map(|&pos| paths.distance(&pos).map(|d| (pos, d)))
             .collect();
 
         if reachable.is_empty() {
@@ -141,28 +224,13 @@ impl State {
 
         // Now find which neighbor of `from` to step to
         // Do BFS backwards from chosen_target
-        let mut queue = VecDeque::new();
-        let mut distances = HashMap::new();
-        
-        queue.push_back(chosen_target);
-        distances.insert(chosen_target, 0);
-
-        while let Some(pos) = queue.pop_front() {
-            let dist = distances[&pos];
-
-            for neighbor in self.neighbors(pos) {
-                if (self.is_open(neighbor) || neighbor == from) && !distances.contains_key(&neighbor) {
-                    distances.insert(neighbor, dist + 1);
-                    queue.push_back(neighbor);
-                }
-            }
-        }
+        let paths = search::bfs(chosen_target, walkable, |_| false);
 
         // Find neighbor of `from` with minimal distance, ties by reading order
         let mut candidates: Vec<(usize, (usize, usize))> = Vec::new();
         for neighbor in self.neighbors(from) {
             if !self.is_open(neighbor) { continue; }
-            if let Some(&d) = distances.get(&neighbor) {
+            if let Some(d) = paths.distance(&neighbor) {
                 candidates.push((d, neighbor));
             }
         }
@@ -213,6 +281,7 @@ impl State {
             // Move if not adjacent to enemy
             if adjacent_enemies.is_empty() {
                 if let Some(next_pos) = self.find_move(pos, unit_type) {
+                    trace!("{:?} moves {:?} -> {:?}", unit_type, pos, next_pos);
                     self.units[unit_idx].pos = next_pos;
                 }
             }
@@ -242,6 +311,9 @@ impl State {
                 
                 let attack_power = self.units[unit_idx].attack;
                 self.units[target_idx].hp -= attack_power;
+                if self.units[target_idx].hp <= 0 {
+                    debug!("{:?} at {:?} kills {:?} at {:?}", unit_type, pos, self.units[target_idx].unit_type, self.units[target_idx].pos);
+                }
             }
         }
 
@@ -255,8 +327,8 @@ impl State {
     }
 }
 
-fn part1(input: &str) -> i32 {
-    let mut state = State::from_input(input, 3);
+fn part1(initial: &State) -> i32 {
+    let mut state = initial.clone();
     let mut rounds = 0;
 
     loop {
@@ -266,12 +338,13 @@ fn part1(input: &str) -> i32 {
         rounds += 1;
     }
 
+    info!("combat ends after {} full rounds with {} hit points left", rounds, state.outcome());
     rounds * state.outcome()
 }
 
-fn part2(input: &str) -> i32 {
+fn part2(initial: &State) -> i32 {
     for elf_attack in 4..=200 {
-        let mut state = State::from_input(input, elf_attack);
+        let mut state = initial.with_elf_attack(elf_attack);
         let initial_elf_count = state.units.iter().filter(|u| u.unit_type == UnitType::Elf).count();
         let mut rounds = 0;
 
@@ -289,6 +362,13 @@ fn part2(input: &str) -> i32 {
         }
 
         let final_elf_count = state.units.iter().filter(|u| u.unit_type == UnitType::Elf).count();
+        debug!(
+            "elf attack {}: {} of {} elves survive {} rounds",
+            elf_attack,
+            final_elf_count,
+            initial_elf_count,
+            rounds
+        );
         if final_elf_count == initial_elf_count {
             return rounds * state.outcome();
         }
@@ -297,94 +377,37 @@ fn part2(input: &str) -> i32 {
     0
 }
 
-fn main() {
-    let input = read_input(15);
-
-    println!("Part 1: {}", part1(&input));
-    println!("Part 2: {}", part2(&input));
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
 
     #[test]
     fn test_part1_sample() {
-        let input = "\
-#######
-#.G...#
-#...EG#
-#.#.#G#
-#..G#E#
-#.....#
-#######";
-        assert_eq!(part1(input), 27730);
+        assert_eq!(part1(&State::from_input(EXAMPLES[0], 3).unwrap()), 27730);
     }
 
     #[test]
     fn test_part1_sample2() {
-        let input = "\
-#######
-#G..#E#
-#E#E.E#
-#G.##.#
-#...#E#
-#...E.#
-#######";
-        assert_eq!(part1(input), 36334);
+        assert_eq!(part1(&State::from_input(EXAMPLES[1], 3).unwrap()), 36334);
     }
 
     #[test]
     fn test_part1_sample3() {
-        let input = "\
-#######
-#E..EG#
-#.#G.E#
-#E.##E#
-#G..#.#
-#..E#.#
-#######";
-        assert_eq!(part1(input), 39514);
+        assert_eq!(part1(&State::from_input(EXAMPLES[2], 3).unwrap()), 39514);
     }
 
     #[test]
     fn test_part1_sample4() {
-        let input = "\
-#######
-#E.G#.#
-#.#G..#
-#G.#.G#
-#G..#.#
-#...E.#
-#######";
-        assert_eq!(part1(input), 27755);
+        assert_eq!(part1(&State::from_input(EXAMPLES[3], 3).unwrap()), 27755);
     }
 
     #[test]
     fn test_part1_sample5() {
-        let input = "\
-#######
-#.E...#
-#.#..G#
-#.###.#
-#E#G#G#
-#...#G#
-#######";
-        assert_eq!(part1(input), 28944);
+        assert_eq!(part1(&State::from_input(EXAMPLES[4], 3).unwrap()), 28944);
     }
 
     #[test]
     fn test_part1_sample6() {
-        let input = "\
-#########
-#G......#
-#.E.#...#
-#..##..G#
-#...##..#
-#...#...#
-#.G...G.#
-#.....G.#
-#########";
-        assert_eq!(part1(input), 18740);
+        assert_eq!(part1(&State::from_input(EXAMPLES[5], 3).unwrap()), 18740);
     }
 }
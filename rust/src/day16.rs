@@ -1,10 +1,42 @@
-use aoc2018::read_input;
+use crate::error::{AocError, Result};
+use crate::parse::{captures, exact_ints};
+use crate::{debug, trace, Answer, Solution};
 use std::collections::{HashMap, HashSet};
 
 type Registers = [usize; 4];
+type Manual = (Vec<Sample>, Vec<Instruction>);
+
+pub struct Day16;
+
+/// The examples from the puzzle description, for `aoc run --example N`; a
+/// single sample without a test program
+const EXAMPLES: &[&str] = &["\
+Before: [3, 2, 1, 1]
+9 2 1 2
+After:  [3, 2, 2, 1]"];
+
+impl Solution for Day16 {
+    type Input = Manual;
+
+    fn parse(&self, input: &str) -> Result<Manual> {
+        parse_input(input)
+    }
+
+    fn part1(&self, (samples, _): &Manual) -> Answer {
+        part1(samples).into()
+    }
+
+    fn part2(&self, (samples, test_program): &Manual) -> Answer {
+        part2(samples, test_program).into()
+    }
+
+    fn examples(&self) -> &'static [&'static str] {
+        EXAMPLES
+    }
+}
 
 #[derive(Debug, Clone, Copy)]
-struct Instruction {
+pub struct Instruction {
     opcode: usize,
     a: usize,
     b: usize,
@@ -12,13 +44,29 @@ struct Instruction {
 }
 
 #[derive(Debug, Clone)]
-struct Sample {
+pub struct Sample {
     before: Registers,
     instruction: Instruction,
     after: Registers,
 }
 
-fn parse_input(input: &str) -> (Vec<Sample>, Vec<Instruction>) {
+fn parse_instruction(input: &str, line: &str) -> Result<Instruction> {
+    let [opcode, a, b, c] = exact_ints(input, line)?;
+    if opcode >= 16 {
+        return Err(AocError::at(input, line, format!("opcode {} out of range", opcode)));
+    }
+    if c >= 4 {
+        return Err(AocError::at(input, line, format!("output register {} out of range", c)));
+    }
+    Ok(Instruction { opcode, a, b, c })
+}
+
+fn parse_registers(input: &str, line: &str, prefix: &str) -> Result<Registers> {
+    let [values] = captures(input, line, &format!("{}{{}}]", prefix))?;
+    exact_ints(input, values)
+}
+
+fn parse_input(input: &str) -> Result<Manual> {
     let parts: Vec<&str> = input.split("\n\n\n").collect();
     
     let samples = parts[0]
@@ -26,69 +74,56 @@ fn parse_input(input: &str) -> (Vec<Sample>, Vec<Instruction>) {
         .filter(|s| !s.is_empty())
         .map(|sample| {
             let lines: Vec<&str> = sample.lines().collect();
-            let before_str = lines[0].trim_start_matches("Before: [").trim_end_matches(']');
-            let before: Vec<usize> = before_str.split(", ").map(|n| n.parse().unwrap()).collect();
-            
-            let inst_parts: Vec<usize> = lines[1].split_whitespace().map(|n| n.parse().unwrap()).collect();
-            let instruction = Instruction {
-                opcode: inst_parts[0],
-                a: inst_parts[1],
-                b: inst_parts[2],
-                c: inst_parts[3],
-            };
-            
-            let after_str = lines[2].trim_start_matches("After:  [").trim_end_matches(']');
-            let after: Vec<usize> = after_str.split(", ").map(|n| n.parse().unwrap()).collect();
-            
-            Sample {
-                before: [before[0], before[1], before[2], before[3]],
-                instruction,
-                after: [after[0], after[1], after[2], after[3]],
+            if lines.len() != 3 {
+                return Err(AocError::at(input, sample, "expected a Before/instruction/After sample"));
             }
+
+            Ok(Sample {
+                before: parse_registers(input, lines[0], "Before: [")?,
+                instruction: parse_instruction(input, lines[1])?,
+                after: parse_registers(input, lines[2], "After:  [")?,
+            })
         })
-        .collect();
+        .collect::<Result<_>>()?;
     
     let test_program = if parts.len() > 1 {
         parts[1]
             .lines()
             .filter(|l| !l.is_empty())
-            .map(|line| {
-                let parts: Vec<usize> = line.split_whitespace().map(|n| n.parse().unwrap()).collect();
-                Instruction {
-                    opcode: parts[0],
-                    a: parts[1],
-                    b: parts[2],
-                    c: parts[3],
-                }
-            })
-            .collect()
+            .map(|line| parse_instruction(input, line))
+            .collect::<Result<_>>()?
     } else {
         Vec::new()
     };
     
-    (samples, test_program)
+    Ok((samples, test_program))
 }
 
-fn execute(opcode: &str, regs: &mut Registers, a: usize, b: usize, c: usize) {
-    match opcode {
-        "addr" => regs[c] = regs[a] + regs[b],
-        "addi" => regs[c] = regs[a] + b,
-        "mulr" => regs[c] = regs[a] * regs[b],
-        "muli" => regs[c] = regs[a] * b,
-        "banr" => regs[c] = regs[a] & regs[b],
-        "bani" => regs[c] = regs[a] & b,
-        "borr" => regs[c] = regs[a] | regs[b],
-        "bori" => regs[c] = regs[a] | b,
-        "setr" => regs[c] = regs[a],
-        "seti" => regs[c] = a,
-        "gtir" => regs[c] = if a > regs[b] { 1 } else { 0 },
-        "gtri" => regs[c] = if regs[a] > b { 1 } else { 0 },
-        "gtrr" => regs[c] = if regs[a] > regs[b] { 1 } else { 0 },
-        "eqir" => regs[c] = if a == regs[b] { 1 } else { 0 },
-        "eqri" => regs[c] = if regs[a] == b { 1 } else { 0 },
-        "eqrr" => regs[c] = if regs[a] == regs[b] { 1 } else { 0 },
+/// Run one instruction, or return `None` if it reads or writes a register
+/// that doesn't exist
+fn execute(opcode: &str, regs: &mut Registers, a: usize, b: usize, c: usize) -> Option<()> {
+    let reg = |r: usize| regs.get(r).copied();
+    let value = match opcode {
+        "addr" => reg(a)? + reg(b)?,
+        "addi" => reg(a)? + b,
+        "mulr" => reg(a)? * reg(b)?,
+        "muli" => reg(a)? * b,
+        "banr" => reg(a)? & reg(b)?,
+        "bani" => reg(a)? & b,
+        "borr" => reg(a)? | reg(b)?,
+        "bori" => reg(a)? | b,
+        "setr" => reg(a)?,
+        "seti" => a,
+        "gtir" => usize::from(a > reg(b)?),
+        "gtri" => usize::from(reg(a)? > b),
+        "gtrr" => usize::from(reg(a)? > reg(b)?),
+        "eqir" => usize::from(a == reg(b)?),
+        "eqri" => usize::from(reg(a)? == b),
+        "eqrr" => usize::from(reg(a)? == reg(b)?),
         _ => panic!("Unknown opcode: {}", opcode),
-    }
+    };
+    *regs.get_mut(c)? = value;
+    Some(())
 }
 
 fn get_all_opcodes() -> Vec<&'static str> {
@@ -100,12 +135,11 @@ fn get_all_opcodes() -> Vec<&'static str> {
 
 fn matches_sample(sample: &Sample, opcode: &str) -> bool {
     let mut regs = sample.before;
-    execute(opcode, &mut regs, sample.instruction.a, sample.instruction.b, sample.instruction.c);
-    regs == sample.after
+    let Instruction { a, b, c, .. } = sample.instruction;
+    execute(opcode, &mut regs, a, b, c).is_some() && regs == sample.after
 }
 
-fn part1(input: &str) -> usize {
-    let (samples, _) = parse_input(input);
+fn part1(samples: &[Sample]) -> usize {
     let opcodes = get_all_opcodes();
     
     samples.iter()
@@ -118,8 +152,7 @@ fn part1(input: &str) -> usize {
         .count()
 }
 
-fn part2(input: &str) -> usize {
-    let (samples, test_program) = parse_input(input);
+fn part2(samples: &[Sample], test_program: &[Instruction]) -> usize {
     let opcodes = get_all_opcodes();
     
     // Build a map of opcode number -> possible opcode names
@@ -129,7 +162,7 @@ fn part2(input: &str) -> usize {
     }
     
     // For each sample, eliminate opcodes that don't match
-    for sample in &samples {
+    for sample in samples {
         let opcode_num = sample.instruction.opcode;
         let matches: HashSet<&str> = opcodes.iter()
             .filter(|&&op| matches_sample(sample, op))
@@ -138,6 +171,7 @@ fn part2(input: &str) -> usize {
         
         if let Some(poss) = possible.get_mut(&opcode_num) {
             *poss = poss.intersection(&matches).copied().collect();
+            trace!("sample {:?} leaves opcode {} with {} candidates", sample.instruction, opcode_num, poss.len());
         }
     }
     
@@ -155,12 +189,14 @@ fn part2(input: &str) -> usize {
         }
         
         if let Some((num, op)) = found {
+            debug!("opcode {} must be {}", num, op);
             opcode_map.insert(num, op);
             // Remove this opcode from all other possibilities
             for poss in possible.values_mut() {
                 poss.remove(op);
             }
         } else {
+            debug!("stuck with {} of 16 opcodes deduced: {:?}", opcode_map.len(), possible);
             break;
         }
     }
@@ -169,31 +205,24 @@ fn part2(input: &str) -> usize {
     let mut regs = [0, 0, 0, 0];
     for inst in test_program {
         if let Some(&opcode) = opcode_map.get(&inst.opcode) {
-            execute(opcode, &mut regs, inst.a, inst.b, inst.c);
+            if execute(opcode, &mut regs, inst.a, inst.b, inst.c).is_none() {
+                debug!("{:?} as {} uses a register out of range, skipping it", inst, opcode);
+            }
         }
     }
     
     regs[0]
 }
 
-fn main() {
-    let input = read_input(16);
-
-    println!("Part 1: {}", part1(&input));
-    println!("Part 2: {}", part2(&input));
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
 
     #[test]
     fn test_part1() {
-        let input = "Before: [3, 2, 1, 1]
-9 2 1 2
-After:  [3, 2, 2, 1]";
         // This sample matches mulr, addi, and seti (3 opcodes)
-        assert_eq!(part1(input), 1);
+        let (samples, _) = parse_input(EXAMPLES[0]).unwrap();
+        assert_eq!(part1(&samples), 1);
     }
 
     #[test]
@@ -208,4 +237,20 @@ After:  [3, 2, 2, 1]";
         assert!(matches_sample(&sample, "addi")); // reg[2]=1 + val 1 = 2
         assert!(matches_sample(&sample, "seti")); // val 2 -> reg[2]
     }
+
+    #[test]
+    fn test_instruction_out_of_range() {
+        for (line, message) in [("16 2 1 2", "opcode 16"), ("9 2 1 4", "output register 4")] {
+            let err = parse_instruction(line, line).unwrap_err();
+            assert!(err.to_string().contains(message), "{}", err);
+        }
+
+        // Inputs can be any value, they only have to be registers for some opcodes
+        let input = "Before: [3, 2, 1, 1]\n9 7 4 2\nAfter:  [3, 2, 7, 1]";
+        let (samples, _) = parse_input(input).unwrap();
+        assert!(matches_sample(&samples[0], "seti"));
+        assert!(!matches_sample(&samples[0], "setr"));
+        assert!(!matches_sample(&samples[0], "addr"));
+        assert_eq!(part1(&samples), 0);
+    }
 }
@@ -1,34 +1,75 @@
-use aoc2018::read_input;
-use std::collections::HashSet;
-
-struct Grid {
-    clay: HashSet<(i32, i32)>,
-    water_flow: HashSet<(i32, i32)>,
-    water_rest: HashSet<(i32, i32)>,
-    min_y: i32,
-    max_y: i32,
+use crate::error::{parse_at, AocError, Result};
+use crate::grid::SparseGrid;
+use crate::parse::captures;
+use crate::{debug, trace, Answer, Solution};
+
+type Clay = SparseGrid<Tile>;
+
+pub struct Day17;
+
+/// The examples from the puzzle description, for `aoc run --example N`
+const EXAMPLES: &[&str] = &[
+    "\
+x=495, y=2..7
+y=7, x=495..501
+x=501, y=3..7
+x=498, y=2..4
+x=506, y=1..2
+x=498, y=10..13
+x=504, y=10..13
+y=13, x=498..504",
+];
+
+impl Solution for Day17 {
+    type Input = Clay;
+
+    fn parse(&self, input: &str) -> Result<Clay> {
+        parse_input(input)
+    }
+
+    fn part1(&self, clay: &Clay) -> Answer {
+        part1(clay).into()
+    }
+
+    fn part2(&self, clay: &Clay) -> Answer {
+        part2(clay).into()
+    }
+
+    fn examples(&self) -> &'static [&'static str] {
+        EXAMPLES
+    }
 }
 
-impl Grid {
-    fn new(clay: HashSet<(i32, i32)>) -> Self {
-        let min_y = clay.iter().map(|(_, y)| *y).min().unwrap();
-        let max_y = clay.iter().map(|(_, y)| *y).max().unwrap();
-        
-        Grid {
-            clay,
-            water_flow: HashSet::new(),
-            water_rest: HashSet::new(),
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Tile {
+    Clay,
+    Flowing,
+    Resting,
+}
+
+struct Ground {
+    tiles: SparseGrid<Tile>,
+    min_y: i64,
+    max_y: i64,
+}
+
+impl Ground {
+    fn new(clay: &Clay) -> Self {
+        let ((_, min_y), (_, max_y)) = clay.bounds().expect("parse_input rejects a scan without clay");
+
+        Ground {
+            tiles: clay.clone(),
             min_y,
             max_y,
         }
     }
     
-    fn is_blocked(&self, x: i32, y: i32) -> bool {
-        self.clay.contains(&(x, y)) || self.water_rest.contains(&(x, y))
+    fn is_blocked(&self, x: i64, y: i64) -> bool {
+        matches!(self.tiles.get((x, y)), Some(Tile::Clay | Tile::Resting))
     }
     
-    fn flow(&mut self, x: i32, y: i32) {
-        if y > self.max_y || self.water_flow.contains(&(x, y)) {
+    fn flow(&mut self, x: i64, y: i64) {
+        if y > self.max_y || self.tiles.get((x, y)) == Some(&Tile::Flowing) {
             return;
         }
         
@@ -36,7 +77,7 @@ impl Grid {
             return;
         }
         
-        self.water_flow.insert((x, y));
+        self.tiles.insert((x, y), Tile::Flowing);
         
         // Try flowing down
         if !self.is_blocked(x, y + 1) {
@@ -49,14 +90,14 @@ impl Grid {
             let mut left_x = x;
             while !self.is_blocked(left_x - 1, y) && self.is_blocked(left_x - 1, y + 1) {
                 left_x -= 1;
-                self.water_flow.insert((left_x, y));
+                self.tiles.insert((left_x, y), Tile::Flowing);
             }
             
             // Spread right  
             let mut right_x = x;
             while !self.is_blocked(right_x + 1, y) && self.is_blocked(right_x + 1, y + 1) {
                 right_x += 1;
-                self.water_flow.insert((right_x, y));
+                self.tiles.insert((right_x, y), Tile::Flowing);
             }
             
             // Check if water is contained (walls on both sides)
@@ -64,10 +105,10 @@ impl Grid {
             let right_wall = self.is_blocked(right_x + 1, y);
             
             if left_wall && right_wall {
+                trace!("water settles at y={} from x={} to {}", y, left_x, right_x);
                 // Water settles - convert flow to rest
                 for i in left_x..=right_x {
-                    self.water_flow.remove(&(i, y));
-                    self.water_rest.insert((i, y));
+                    self.tiles.insert((i, y), Tile::Resting);
                 }
             } else {
                 // Water can flow off one or both sides
@@ -81,60 +122,82 @@ impl Grid {
         }
     }
     
-    fn count_water(&self) -> usize {
-        self.water_flow.iter()
-            .chain(self.water_rest.iter())
-            .filter(|(_, y)| *y >= self.min_y && *y <= self.max_y)
+    /// Tiles of any of `kinds` within the scanned rows
+    fn count(&self, kinds: &[Tile]) -> usize {
+        self.tiles
+            .iter()
+            .filter(|((_, y), tile)| kinds.contains(tile) && (self.min_y..=self.max_y).contains(y))
             .count()
     }
+
+    fn count_water(&self) -> usize {
+        self.count(&[Tile::Flowing, Tile::Resting])
+    }
     
     fn count_resting_water(&self) -> usize {
-        self.water_rest.iter()
-            .filter(|(_, y)| *y >= self.min_y && *y <= self.max_y)
-            .count()
+        self.count(&[Tile::Resting])
+    }
+
+    /// The ground as drawn in the puzzle description
+    fn render(&self) -> String {
+        self.tiles.render(|tile| match tile {
+            None => '.',
+            Some(Tile::Clay) => '#',
+            Some(Tile::Flowing) => '|',
+            Some(Tile::Resting) => '~',
+        })
     }
 }
 
-fn parse_input(input: &str) -> HashSet<(i32, i32)> {
-    let mut clay = HashSet::new();
+fn parse_input(input: &str) -> Result<Clay> {
+    let mut clay = SparseGrid::new();
     
-    for line in input.lines() {
-        let parts: Vec<&str> = line.split(", ").collect();
-        
-        let (first_var, first_val) = parts[0].split_once('=').unwrap();
-        let first_val: i32 = first_val.parse().unwrap();
-        
-        let (_, range_str) = parts[1].split_once('=').unwrap();
-        let range_parts: Vec<&str> = range_str.split("..").collect();
-        let start: i32 = range_parts[0].parse().unwrap();
-        let end: i32 = range_parts[1].parse().unwrap();
+    for line in input.lines().filter(|line| !line.trim().is_empty()) {
+        let [first_var, first_val, range_var, start, end] = captures(input, line, "{}={}, {}={}..{}")?;
+        let vertical = match (first_var, range_var) {
+            ("x", "y") => true,
+            ("y", "x") => false,
+            _ => return Err(AocError::at(input, line, "expected `x=495, y=2..7` or `y=7, x=495..501`")),
+        };
+        let first_val: i64 = parse_at(input, first_val)?;
+        let start: i64 = parse_at(input, start)?;
+        let end: i64 = parse_at(input, end)?;
         
         for i in start..=end {
-            let (x, y) = if first_var == "x" {
+            let (x, y) = if vertical {
                 (first_val, i)
             } else {
                 (i, first_val)
             };
-            clay.insert((x, y));
+            clay.insert((x, y), Tile::Clay);
         }
     }
-    
-    clay
+
+    if clay.is_empty() {
+        return Err(AocError::at(input, input, "no clay veins in input"));
+    }
+    Ok(clay)
 }
 
-fn part1(input: &str) -> usize {
-    let clay = parse_input(input);
-    let mut grid = Grid::new(clay);
+fn part1(clay: &Clay) -> usize {
+    let mut grid = Ground::new(clay);
     
     // Start water flow from spring at (500, 0)
     grid.flow(500, 0);
+    debug!(
+        "{} tiles of flowing and {} of resting water, counting y {}..={}",
+        grid.count(&[Tile::Flowing]),
+        grid.count(&[Tile::Resting]),
+        grid.min_y,
+        grid.max_y
+    );
+    trace!("the ground after the water settled:\n{}", grid.render());
     
     grid.count_water()
 }
 
-fn part2(input: &str) -> usize {
-    let clay = parse_input(input);
-    let mut grid = Grid::new(clay);
+fn part2(clay: &Clay) -> usize {
+    let mut grid = Ground::new(clay);
     
     // Start water flow from spring at (500, 0)
     grid.flow(500, 0);
@@ -142,40 +205,17 @@ fn part2(input: &str) -> usize {
     grid.count_resting_water()
 }
 
-fn main() {
-    let input = read_input(17);
-
-    println!("Part 1: {}", part1(&input));
-    println!("Part 2: {}", part2(&input));
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
 
     #[test]
     fn test_part1() {
-        let input = "x=495, y=2..7
-y=7, x=495..501
-x=501, y=3..7
-x=498, y=2..4
-x=506, y=1..2
-x=498, y=10..13
-x=504, y=10..13
-y=13, x=498..504";
-        assert_eq!(part1(input), 57);
+        assert_eq!(part1(&parse_input(EXAMPLES[0]).unwrap()), 57);
     }
 
     #[test]
     fn test_part2() {
-        let input = "x=495, y=2..7
-y=7, x=495..501
-x=501, y=3..7
-x=498, y=2..4
-x=506, y=1..2
-x=498, y=10..13
-x=504, y=10..13
-y=13, x=498..504";
-        assert_eq!(part2(input), 29);
+        assert_eq!(part2(&parse_input(EXAMPLES[0]).unwrap()), 29);
     }
 }
@@ -1,125 +1,107 @@
-use aoc2018::read_input;
-use std::collections::HashMap;
+use crate::cycle;
+use crate::error::Result;
+use crate::grid::Grid;
+use crate::{debug, Answer, Solution};
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
-enum Acre {
+type Area = Grid<Acre>;
+
+pub struct Day18;
+
+/// The examples from the puzzle description, for `aoc run --example N`
+const EXAMPLES: &[&str] = &[
+    "\
+.#.#...|#.
+.....#|##|
+.|..|...#.
+..|#.....#
+#.#|||#|#|
+...#.||...
+.|....|...
+||...#|.#|
+|.||||..|.
+...#.|..|.",
+];
+
+impl Solution for Day18 {
+    type Input = Area;
+
+    fn parse(&self, input: &str) -> Result<Area> {
+        parse_input(input)
+    }
+
+    fn part1(&self, area: &Area) -> Answer {
+        part1(area).into()
+    }
+
+    fn part2(&self, area: &Area) -> Answer {
+        part2(area).into()
+    }
+
+    fn examples(&self) -> &'static [&'static str] {
+        EXAMPLES
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Acre {
     Open,
     Trees,
     Lumberyard,
 }
 
-fn parse_input(input: &str) -> Vec<Vec<Acre>> {
-    input
-        .lines()
-        .map(|line| {
-            line.chars()
-                .map(|ch| match ch {
-                    '.' => Acre::Open,
-                    '|' => Acre::Trees,
-                    '#' => Acre::Lumberyard,
-                    _ => panic!("Invalid character: {}", ch),
-                })
-                .collect()
-        })
-        .collect()
-}
-
-fn count_adjacent(grid: &Vec<Vec<Acre>>, row: usize, col: usize, acre_type: Acre) -> usize {
-    let rows = grid.len() as i32;
-    let cols = grid[0].len() as i32;
-    let r = row as i32;
-    let c = col as i32;
-    
-    let mut count = 0;
-    for dr in -1..=1 {
-        for dc in -1..=1 {
-            if dr == 0 && dc == 0 {
-                continue;
-            }
-            let nr = r + dr;
-            let nc = c + dc;
-            if nr >= 0 && nr < rows && nc >= 0 && nc < cols {
-                if grid[nr as usize][nc as usize] == acre_type {
-                    count += 1;
-                }
-            }
+impl Acre {
+    fn parse(c: char) -> Option<Self> {
+        match c {
+            '.' => Some(Acre::Open),
+            '|' => Some(Acre::Trees),
+            '#' => Some(Acre::Lumberyard),
+            _ => None,
         }
     }
-    count
 }
 
-fn simulate_minute(grid: &Vec<Vec<Acre>>) -> Vec<Vec<Acre>> {
-    let rows = grid.len();
-    let cols = grid[0].len();
-    let mut new_grid = grid.clone();
-    
-    for r in 0..rows {
-        for c in 0..cols {
-            new_grid[r][c] = match grid[r][c] {
-                Acre::Open => {
-                    if count_adjacent(grid, r, c, Acre::Trees) >= 3 {
-                        Acre::Trees
-                    } else {
-                        Acre::Open
-                    }
-                }
-                Acre::Trees => {
-                    if count_adjacent(grid, r, c, Acre::Lumberyard) >= 3 {
-                        Acre::Lumberyard
-                    } else {
-                        Acre::Trees
-                    }
-                }
-                Acre::Lumberyard => {
-                    let has_lumberyard = count_adjacent(grid, r, c, Acre::Lumberyard) >= 1;
-                    let has_trees = count_adjacent(grid, r, c, Acre::Trees) >= 1;
-                    if has_lumberyard && has_trees {
-                        Acre::Lumberyard
-                    } else {
-                        Acre::Open
-                    }
-                }
-            };
-        }
-    }
-    
-    new_grid
+fn parse_input(input: &str) -> Result<Area> {
+    Grid::parse(input, Acre::parse)
 }
 
-fn count_resource_value(grid: &Vec<Vec<Acre>>) -> usize {
-    let mut trees = 0;
-    let mut lumberyards = 0;
-    
-    for row in grid {
-        for &acre in row {
-            match acre {
-                Acre::Trees => trees += 1,
-                Acre::Lumberyard => lumberyards += 1,
-                _ => {}
+fn count_adjacent(grid: &Area, pos: (usize, usize), acre_type: Acre) -> usize {
+    grid.neighbours8(pos).filter(|&p| grid[p] == acre_type).count()
+}
+
+fn simulate_minute(grid: &Area) -> Area {
+    grid.map(|pos, &acre| match acre {
+        Acre::Open => {
+            if count_adjacent(grid, pos, Acre::Trees) >= 3 {
+                Acre::Trees
+            } else {
+                Acre::Open
             }
         }
-    }
-    
-    trees * lumberyards
+        Acre::Trees => {
+            if count_adjacent(grid, pos, Acre::Lumberyard) >= 3 {
+                Acre::Lumberyard
+            } else {
+                Acre::Trees
+            }
+        }
+        Acre::Lumberyard => {
+            let has_lumberyard = count_adjacent(grid, pos, Acre::Lumberyard) >= 1;
+            let has_trees = count_adjacent(grid, pos, Acre::Trees) >= 1;
+            if has_lumberyard && has_trees {
+                Acre::Lumberyard
+            } else {
+                Acre::Open
+            }
+        }
+    })
 }
 
-fn grid_to_string(grid: &Vec<Vec<Acre>>) -> String {
-    grid.iter()
-        .map(|row| {
-            row.iter()
-                .map(|&acre| match acre {
-                    Acre::Open => '.',
-                    Acre::Trees => '|',
-                    Acre::Lumberyard => '#',
-                })
-                .collect::<String>()
-        })
-        .collect::<Vec<_>>()
-        .join("\n")
+fn count_resource_value(grid: &Area) -> usize {
+    grid.count(|&acre| acre == Acre::Trees) * grid.count(|&acre| acre == Acre::Lumberyard)
 }
 
-fn part1(input: &str) -> usize {
-    let mut grid = parse_input(input);
+fn part1(area: &Area) -> usize {
+    let mut grid = area.clone();
     
     for _ in 0..10 {
         grid = simulate_minute(&grid);
@@ -128,40 +110,15 @@ fn part1(input: &str) -> usize {
     count_resource_value(&grid)
 }
 
-fn part2(input: &str) -> usize {
-    let mut grid = parse_input(input);
-    let mut seen: HashMap<String, usize> = HashMap::new();
+fn part2(area: &Area) -> usize {
     let target = 1_000_000_000;
     
-    for minute in 0..target {
-        let state = grid_to_string(&grid);
-        
-        if let Some(&prev_minute) = seen.get(&state) {
-            // Found a cycle
-            let cycle_length = minute - prev_minute;
-            let remaining = target - minute;
-            let final_offset = remaining % cycle_length;
-            
-            // Simulate the remaining steps
-            for _ in 0..final_offset {
-                grid = simulate_minute(&grid);
-            }
-            
-            return count_resource_value(&grid);
-        }
-        
-        seen.insert(state, minute);
-        grid = simulate_minute(&grid);
-    }
-    
-    count_resource_value(&grid)
-}
-
-fn main() {
-    let input = read_input(18);
-
-    println!("Part 1: {}", part1(&input));
-    println!("Part 2: {}", part2(&input));
+    // Only a hash of each minute's area is kept, and the simulation goes on
+    // from the repeat instead of starting over
+    let grid = cycle::nth_state(area.clone(), target, simulate_minute, cycle::hash);
+    let value = count_resource_value(&grid);
+    debug!("minute {} has a resource value of {}", target, value);
+    value
 }
 
 #[cfg(test)]
@@ -170,16 +127,15 @@ mod tests {
 
     #[test]
     fn test_part1() {
-        let input = ".#.#...|#.
-.....#|##|
-.|..|...#.
-..|#.....#
-#.#|||#|#|
-...#.||...
-.|....|...
-||...#|.#|
-|.||||..|.
-...#.|..|.";
-        assert_eq!(part1(input), 1147);
+        assert_eq!(part1(&parse_input(EXAMPLES[0]).unwrap()), 1147);
+    }
+
+    #[test]
+    fn test_part2() {
+        // The example dies out and then stays the same
+        let area = parse_input(EXAMPLES[0]).unwrap();
+        let cycle = cycle::find_cycle(area.clone(), simulate_minute, cycle::hash);
+        assert_eq!(cycle.length, 1);
+        assert_eq!(part2(&area), 0);
     }
 }
@@ -1,7 +1,43 @@
-use aoc2018::read_input;
+use crate::error::{parse_at, AocError, Result};
+use crate::{debug, Answer, Solution};
 
 type Registers = [i64; 6];
 
+pub struct Day19;
+
+/// The examples from the puzzle description, for `aoc run --example N`
+const EXAMPLES: &[&str] = &[
+    "\
+#ip 0
+seti 5 0 1
+seti 6 0 2
+addi 0 1 0
+addr 1 2 3
+setr 1 0 0
+seti 8 0 4
+seti 9 0 5",
+];
+
+impl Solution for Day19 {
+    type Input = Program;
+
+    fn parse(&self, input: &str) -> Result<Program> {
+        parse_input(input)
+    }
+
+    fn part1(&self, program: &Program) -> Answer {
+        run_program(program, 0).into()
+    }
+
+    fn part2(&self, program: &Program) -> Answer {
+        part2(program).into()
+    }
+
+    fn examples(&self) -> &'static [&'static str] {
+        EXAMPLES
+    }
+}
+
 #[derive(Debug, Clone)]
 struct Instruction {
     opcode: String,
@@ -10,11 +46,16 @@ struct Instruction {
     c: i64,
 }
 
-struct Program {
+pub struct Program {
     ip_register: usize,
     instructions: Vec<Instruction>,
 }
 
+const OPCODES: [&str; 16] = [
+    "addr", "addi", "mulr", "muli", "banr", "bani", "borr", "bori",
+    "setr", "seti", "gtir", "gtri", "gtrr", "eqir", "eqri", "eqrr",
+];
+
 fn execute(opcode: &str, regs: &mut Registers, a: i64, b: i64, c: i64) {
     let a = a as usize;
     let b = b as usize;
@@ -41,31 +82,44 @@ fn execute(opcode: &str, regs: &mut Registers, a: i64, b: i64, c: i64) {
     }
 }
 
-fn parse_input(input: &str) -> Program {
-    let lines: Vec<&str> = input.lines().collect();
-    let ip_register = lines[0]
+fn parse_input(input: &str) -> Result<Program> {
+    let lines: Vec<&str> = input.lines().filter(|line| !line.trim().is_empty()).collect();
+    let first = lines.first().copied().unwrap_or(input);
+    let ip_field = first
         .strip_prefix("#ip ")
-        .unwrap()
-        .parse()
-        .unwrap();
+        .ok_or_else(|| AocError::at(input, first, "expected `#ip <register>`"))?;
+    let ip_register: usize = parse_at(input, ip_field)?;
+    if ip_register >= 6 {
+        return Err(AocError::at(input, ip_field, "instruction pointer register out of range"));
+    }
     
     let instructions = lines[1..]
         .iter()
         .map(|line| {
             let parts: Vec<&str> = line.split_whitespace().collect();
-            Instruction {
-                opcode: parts[0].to_string(),
-                a: parts[1].parse().unwrap(),
-                b: parts[2].parse().unwrap(),
-                c: parts[3].parse().unwrap(),
+            if parts.len() != 4 {
+                return Err(AocError::at(input, line, "expected `opcode a b c`"));
+            }
+            if !OPCODES.contains(&parts[0]) {
+                return Err(AocError::at(input, parts[0], format!("unknown opcode {:?}", parts[0])));
             }
+            let c = parse_at(input, parts[3])?;
+            if !(0..6).contains(&c) {
+                return Err(AocError::at(input, parts[3], "output register out of range"));
+            }
+            Ok(Instruction {
+                opcode: parts[0].to_string(),
+                a: parse_at(input, parts[1])?,
+                b: parse_at(input, parts[2])?,
+                c,
+            })
         })
-        .collect();
+        .collect::<Result<_>>()?;
     
-    Program {
+    Ok(Program {
         ip_register,
         instructions,
-    }
+    })
 }
 
 fn run_program(program: &Program, initial_reg0: i64) -> i64 {
@@ -97,14 +151,7 @@ fn sum_of_divisors(n: i64) -> i64 {
     sum
 }
 
-fn part1(input: &str) -> i64 {
-    let program = parse_input(input);
-    run_program(&program, 0)
-}
-
-fn part2(input: &str) -> i64 {
-    let program = parse_input(input);
-    
+fn part2(program: &Program) -> i64 {
     // The program calculates the sum of divisors of a number stored in register 1.
     // With register 0 starting at 1, the initialization phase builds a much larger
     // target number. Running the full program would take too long, so we:
@@ -131,30 +178,16 @@ fn part2(input: &str) -> i64 {
     
     // Calculate sum of divisors efficiently
     let target = regs[1];
+    debug!("initialization done after {} instructions, summing divisors of {}", iterations, target);
     sum_of_divisors(target)
 }
 
-fn main() {
-    let input = read_input(19);
-
-    println!("Part 1: {}", part1(&input));
-    println!("Part 2: {}", part2(&input));
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
 
     #[test]
     fn test_part1() {
-        let input = "#ip 0
-seti 5 0 1
-seti 6 0 2
-addi 0 1 0
-addr 1 2 3
-setr 1 0 0
-seti 8 0 4
-seti 9 0 5";
-        assert_eq!(run_program(&parse_input(input), 0), 6);
+        assert_eq!(run_program(&parse_input(EXAMPLES[0]).unwrap(), 0), 6);
     }
 }
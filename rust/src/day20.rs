@@ -1,15 +1,50 @@
-use aoc2018::read_input;
+use crate::error::{AocError, Result};
+use crate::search;
+use crate::{debug, trace, Answer, Solution};
 use std::collections::HashMap;
 
-fn parse_regex(regex: &str) -> HashMap<(i32, i32), i32> {
-    let chars: Vec<char> = regex.chars().collect();
-    let mut distances: HashMap<(i32, i32), i32> = HashMap::new();
+type Distances = HashMap<(i32, i32), usize>;
+
+pub struct Day20;
+
+/// The examples from the puzzle description, for `aoc run --example N`
+const EXAMPLES: &[&str] = &[
+    "^WNE$",
+    "^ENWWW(NEEE|SSE(EE|N))$",
+    "^ENNWSWW(NEWS|)SSSEEN(WNSE|)EE(SWEN|)NNN$",
+    "^ESSWWN(E|NNENN(EESS(WNSE|)SSS|WWWSSSSE(SW|NNNE)))$",
+    "^WSSEESWWWNW(S|NENNEEEENN(ESSSSW(NWSW|SSEN)|WSWWN(E|WWS(E|SS))))$",
+];
+
+impl Solution for Day20 {
+    type Input = Distances;
+
+    fn parse(&self, input: &str) -> Result<Distances> {
+        parse_regex(input.trim())
+    }
+
+    fn part1(&self, distances: &Distances) -> Answer {
+        part1(distances).into()
+    }
+
+    fn part2(&self, distances: &Distances) -> Answer {
+        part2(distances).into()
+    }
+
+    fn examples(&self) -> &'static [&'static str] {
+        EXAMPLES
+    }
+}
+
+/// Follow every route in `regex` and return the distance to each room
+fn parse_regex(regex: &str) -> Result<Distances> {
+    // Rooms and the rooms their doors lead to
+    let mut doors: HashMap<(i32, i32), Vec<(i32, i32)>> = HashMap::new();
     let mut stack: Vec<(i32, i32)> = Vec::new();
     let mut pos = (0, 0);
     
-    distances.insert(pos, 0);
-    
-    for &ch in &chars {
+    for (i, ch) in regex.char_indices() {
+        let unbalanced = || AocError::at(regex, &regex[i..], format!("unbalanced {:?}", ch));
         match ch {
             '^' | '$' => {},
             'N' | 'S' | 'E' | 'W' => {
@@ -21,59 +56,60 @@ fn parse_regex(regex: &str) -> HashMap<(i32, i32), i32> {
                     _ => unreachable!(),
                 };
                 
-                let curr_dist = *distances.get(&pos).unwrap_or(&0);
-                let next_dist = distances.entry(next_pos).or_insert(i32::MAX);
-                *next_dist = (*next_dist).min(curr_dist + 1);
+                doors.entry(pos).or_default().push(next_pos);
+                doors.entry(next_pos).or_default().push(pos);
                 
                 pos = next_pos;
             },
             '(' => {
+                trace!("branch at {:?}, depth {}", pos, stack.len() + 1);
                 stack.push(pos);
             },
             ')' => {
-                pos = stack.pop().unwrap();
+                pos = stack.pop().ok_or_else(unbalanced)?;
             },
             '|' => {
-                pos = *stack.last().unwrap();
+                pos = *stack.last().ok_or_else(unbalanced)?;
+            },
+            _ => {
+                return Err(AocError::at(regex, &regex[i..], format!("unexpected character {:?}", ch)));
             },
-            _ => {},
         }
     }
-    
-    distances
+
+    if !stack.is_empty() {
+        return Err(AocError::at(regex, &regex[regex.len()..], "unclosed '('"));
+    }
+    let distances = search::bfs((0, 0), |room| doors.get(room).cloned().unwrap_or_default(), |_| false).into_distances();
+    debug!("{} rooms reached", distances.len());
+    Ok(distances)
 }
 
-fn part1(input: &str) -> i32 {
-    let regex = input.trim();
-    let distances = parse_regex(regex);
-    
+fn part1(distances: &Distances) -> usize {
     *distances.values().max().unwrap_or(&0)
 }
 
-fn part2(input: &str) -> usize {
-    let regex = input.trim();
-    let distances = parse_regex(regex);
-    
+fn part2(distances: &Distances) -> usize {
     distances.values().filter(|&&d| d >= 1000).count()
 }
 
-fn main() {
-    let input = read_input(20);
-
-    println!("Part 1: {}", part1(&input));
-    println!("Part 2: {}", part2(&input));
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
 
     #[test]
     fn test_part1() {
-        assert_eq!(part1("^WNE$"), 3);
-        assert_eq!(part1("^ENWWW(NEEE|SSE(EE|N))$"), 10);
-        assert_eq!(part1("^ENNWSWW(NEWS|)SSSEEN(WNSE|)EE(SWEN|)NNN$"), 18);
-        assert_eq!(part1("^ESSWWN(E|NNENN(EESS(WNSE|)SSS|WWWSSSSE(SW|NNNE)))$"), 23);
-        assert_eq!(part1("^WSSEESWWWNW(S|NENNEEEENN(ESSSSW(NWSW|SSEN)|WSWWN(E|WWS(E|SS))))$"), 31);
+        assert_eq!(part1(&parse_regex(EXAMPLES[0]).unwrap()), 3);
+        assert_eq!(part1(&parse_regex(EXAMPLES[1]).unwrap()), 10);
+        assert_eq!(part1(&parse_regex(EXAMPLES[2]).unwrap()), 18);
+        assert_eq!(part1(&parse_regex(EXAMPLES[3]).unwrap()), 23);
+        assert_eq!(part1(&parse_regex(EXAMPLES[4]).unwrap()), 31);
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert!(matches!(parse_regex("^WN)E$"), Err(AocError::Parse { line: 1, column: 4, .. })));
+        assert!(parse_regex("^W(N|E$").is_err());
+        assert!(parse_regex("^WXE$").is_err());
     }
 }
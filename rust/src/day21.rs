@@ -1,16 +1,41 @@
-use aoc2018::read_input;
+use crate::error::{parse_at, AocError, Result};
+use crate::{debug, trace, Answer, Solution};
 use std::collections::HashSet;
 
 type Registers = [i64; 6];
+type Program = (usize, Vec<Instruction>);
+
+pub struct Day21;
+
+impl Solution for Day21 {
+    type Input = Program;
+
+    fn parse(&self, input: &str) -> Result<Program> {
+        parse_input(input)
+    }
+
+    fn part1(&self, program: &Program) -> Answer {
+        part1(program).into()
+    }
+
+    fn part2(&self, program: &Program) -> Answer {
+        part2(program).into()
+    }
+}
 
 #[derive(Debug, Clone)]
-struct Instruction {
+pub struct Instruction {
     opcode: String,
     a: i64,
     b: i64,
     c: i64,
 }
 
+const OPCODES: [&str; 16] = [
+    "addr", "addi", "mulr", "muli", "banr", "bani", "borr", "bori",
+    "setr", "seti", "gtir", "gtri", "gtrr", "eqir", "eqri", "eqrr",
+];
+
 fn execute(opcode: &str, regs: &mut Registers, a: i64, b: i64, c: i64) {
     let a = a as usize;
     let b = b as usize;
@@ -37,32 +62,45 @@ fn execute(opcode: &str, regs: &mut Registers, a: i64, b: i64, c: i64) {
     }
 }
 
-fn parse_input(input: &str) -> (usize, Vec<Instruction>) {
-    let lines: Vec<&str> = input.lines().collect();
-    let ip_register = lines[0]
+fn parse_input(input: &str) -> Result<Program> {
+    let lines: Vec<&str> = input.lines().filter(|line| !line.trim().is_empty()).collect();
+    let first = lines.first().copied().unwrap_or(input);
+    let ip_field = first
         .strip_prefix("#ip ")
-        .unwrap()
-        .parse()
-        .unwrap();
+        .ok_or_else(|| AocError::at(input, first, "expected `#ip <register>`"))?;
+    let ip_register: usize = parse_at(input, ip_field)?;
+    if ip_register >= 6 {
+        return Err(AocError::at(input, ip_field, "instruction pointer register out of range"));
+    }
     
     let instructions = lines[1..]
         .iter()
         .map(|line| {
             let parts: Vec<&str> = line.split_whitespace().collect();
-            Instruction {
-                opcode: parts[0].to_string(),
-                a: parts[1].parse().unwrap(),
-                b: parts[2].parse().unwrap(),
-                c: parts[3].parse().unwrap(),
+            if parts.len() != 4 {
+                return Err(AocError::at(input, line, "expected `opcode a b c`"));
+            }
+            if !OPCODES.contains(&parts[0]) {
+                return Err(AocError::at(input, parts[0], format!("unknown opcode {:?}", parts[0])));
             }
+            let c = parse_at(input, parts[3])?;
+            if !(0..6).contains(&c) {
+                return Err(AocError::at(input, parts[3], "output register out of range"));
+            }
+            Ok(Instruction {
+                opcode: parts[0].to_string(),
+                a: parse_at(input, parts[1])?,
+                b: parse_at(input, parts[2])?,
+                c,
+            })
         })
-        .collect();
+        .collect::<Result<_>>()?;
     
-    (ip_register, instructions)
+    Ok((ip_register, instructions))
 }
 
-fn find_halting_values(input: &str) -> Vec<i64> {
-    let (ip_register, instructions) = parse_input(input);
+fn find_halting_values((ip_register, instructions): &Program) -> Vec<i64> {
+    let ip_register = *ip_register;
     let mut regs: Registers = [0, 0, 0, 0, 0, 0];
     let mut ip: i64 = 0;
     let mut halting_values = Vec::new();
@@ -81,8 +119,13 @@ fn find_halting_values(input: &str) -> Vec<i64> {
         if ip as usize == 28 {
             if seen_values.contains(&regs[1]) {
                 // We've hit a cycle
+                debug!("{} repeats after {} halting values, the last new one is {:?}", regs[1], halting_values.len(), halting_values.last());
                 break;
             }
+            if halting_values.is_empty() {
+                debug!("first halting value {}", regs[1]);
+            }
+            trace!("halting value #{}: {}", halting_values.len() + 1, regs[1]);
             halting_values.push(regs[1]);
             seen_values.insert(regs[1]);
         }
@@ -94,23 +137,16 @@ fn find_halting_values(input: &str) -> Vec<i64> {
     halting_values
 }
 
-fn part1(input: &str) -> i64 {
-    let halting_values = find_halting_values(input);
+fn part1(program: &Program) -> i64 {
+    let halting_values = find_halting_values(program);
     *halting_values.first().unwrap_or(&0)
 }
 
-fn part2(input: &str) -> i64 {
-    let halting_values = find_halting_values(input);
+fn part2(program: &Program) -> i64 {
+    let halting_values = find_halting_values(program);
     *halting_values.last().unwrap_or(&0)
 }
 
-fn main() {
-    let input = read_input(21);
-
-    println!("Part 1: {}", part1(&input));
-    println!("Part 2: {}", part2(&input));
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -118,7 +154,13 @@ mod tests {
     #[test]
     fn test_basic() {
         // This is a complex reverse-engineering problem
-        // The tests would require running the actual program
-        assert!(true);
+        // The tests would require running the actual program, so only
+        // check the instruction semantics the solver relies on
+        let mut regs: Registers = [0, 3, 0, 0, 0, 0];
+        execute("eqrr", &mut regs, 1, 0, 4);
+        assert_eq!(regs[4], 0);
+        regs[0] = 3;
+        execute("eqrr", &mut regs, 1, 0, 4);
+        assert_eq!(regs[4], 1);
     }
 }
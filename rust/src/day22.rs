@@ -1,6 +1,36 @@
-use aoc2018::read_input;
-use std::collections::{HashMap, BinaryHeap};
-use std::cmp::Reverse;
+use crate::error::{parse_at, AocError, Result};
+use crate::search;
+use crate::{debug, trace, Answer, Solution};
+use std::collections::HashMap;
+
+type Scan = (i32, (i32, i32));
+
+pub struct Day22;
+
+/// The examples from the puzzle description, for `aoc run --example N`
+const EXAMPLES: &[&str] = &[
+    "depth: 510\ntarget: 10,10",
+];
+
+impl Solution for Day22 {
+    type Input = Scan;
+
+    fn parse(&self, input: &str) -> Result<Scan> {
+        parse_input(input)
+    }
+
+    fn part1(&self, &(depth, target): &Scan) -> Answer {
+        part1(depth, target).into()
+    }
+
+    fn part2(&self, &(depth, target): &Scan) -> Answer {
+        part2(depth, target).into()
+    }
+
+    fn examples(&self) -> &'static [&'static str] {
+        EXAMPLES
+    }
+}
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 enum RegionType {
@@ -74,20 +104,21 @@ impl Cave {
     }
 }
 
-fn parse_input(input: &str) -> (i32, (i32, i32)) {
-    let lines: Vec<&str> = input.lines().collect();
-    let depth = lines[0].strip_prefix("depth: ").unwrap().parse().unwrap();
-    let target_parts: Vec<i32> = lines[1]
+fn parse_input(input: &str) -> Result<Scan> {
+    let mut lines = input.lines();
+    let depth_line = lines.next().unwrap_or(input);
+    let depth = depth_line
+        .strip_prefix("depth: ")
+        .ok_or_else(|| AocError::at(input, depth_line, "expected `depth: <n>`"))?;
+    let target_line = lines.next().unwrap_or(&input[input.len()..]);
+    let (x, y) = target_line
         .strip_prefix("target: ")
-        .unwrap()
-        .split(',')
-        .map(|s| s.parse().unwrap())
-        .collect();
-    (depth, (target_parts[0], target_parts[1]))
+        .and_then(|target| target.split_once(','))
+        .ok_or_else(|| AocError::at(input, target_line, "expected `target: <x>,<y>`"))?;
+    Ok((parse_at(input, depth)?, (parse_at(input, x)?, parse_at(input, y)?)))
 }
 
-fn part1(input: &str) -> i32 {
-    let (depth, target) = parse_input(input);
+fn part1(depth: i32, target: (i32, i32)) -> i32 {
     let mut cave = Cave::new(depth, target);
     
     let mut risk = 0;
@@ -99,36 +130,18 @@ fn part1(input: &str) -> i32 {
     risk
 }
 
-fn part2(input: &str) -> i32 {
-    let (depth, target) = parse_input(input);
+fn part2(depth: i32, target: (i32, i32)) -> i32 {
     let mut cave = Cave::new(depth, target);
     
-    // Dijkstra's algorithm: (time, x, y, tool)
-    let mut heap: BinaryHeap<Reverse<(i32, i32, i32, Tool)>> = BinaryHeap::new();
-    let mut visited: HashMap<(i32, i32, Tool), i32> = HashMap::new();
-    
-    heap.push(Reverse((0, 0, 0, Tool::Torch)));
-    
-    while let Some(Reverse((time, x, y, tool))) = heap.pop() {
-        if (x, y) == target && tool == Tool::Torch {
-            return time;
-        }
-        
-        if let Some(&prev_time) = visited.get(&(x, y, tool)) {
-            if time >= prev_time {
-                continue;
-            }
-        }
-        visited.insert((x, y, tool), time);
+    // Dijkstra's algorithm over (x, y, tool)
+    let neighbours = |&(x, y, tool): &(i32, i32, Tool)| {
+        trace!("at {},{} with {:?}", x, y, tool);
+        let mut next = Vec::new();
         
         // Try switching tools (7 minutes)
-        let valid_tools = cave.valid_tools(x, y);
-        for &new_tool in &valid_tools {
+        for new_tool in cave.valid_tools(x, y) {
             if new_tool != tool {
-                let new_time = time + 7;
-                if visited.get(&(x, y, new_tool)).map_or(true, |&t| new_time < t) {
-                    heap.push(Reverse((new_time, x, y, new_tool)));
-                }
+                next.push(((x, y, new_tool), 7));
             }
         }
         
@@ -137,26 +150,17 @@ fn part2(input: &str) -> i32 {
             let nx = x + dx;
             let ny = y + dy;
             
-            if nx >= 0 && ny >= 0 && nx <= target.0 + 100 && ny <= target.1 + 100 {
-                let valid_tools = cave.valid_tools(nx, ny);
-                if valid_tools.contains(&tool) {
-                    let new_time = time + 1;
-                    if visited.get(&(nx, ny, tool)).map_or(true, |&t| new_time < t) {
-                        heap.push(Reverse((new_time, nx, ny, tool)));
-                    }
-                }
+            if nx >= 0 && ny >= 0 && nx <= target.0 + 100 && ny <= target.1 + 100 && cave.valid_tools(nx, ny).contains(&tool) {
+                next.push(((nx, ny, tool), 1));
             }
         }
-    }
+        next
+    };
+    let paths = search::dijkstra((0, 0, Tool::Torch), neighbours, |&state| state == (target.0, target.1, Tool::Torch));
     
-    -1 // Should never reach here
-}
-
-fn main() {
-    let input = read_input(22);
-
-    println!("Part 1: {}", part1(&input));
-    println!("Part 2: {}", part2(&input));
+    let time = paths.goal_distance().expect("the target is always reachable");
+    debug!("reached the target in {} minutes after reaching {} states", time, paths.distances().len());
+    time as i32
 }
 
 #[cfg(test)]
@@ -165,13 +169,13 @@ mod tests {
 
     #[test]
     fn test_part1() {
-        let input = "depth: 510\ntarget: 10,10";
-        assert_eq!(part1(input), 114);
+        let (depth, target) = parse_input(EXAMPLES[0]).unwrap();
+        assert_eq!(part1(depth, target), 114);
     }
 
     #[test]
     fn test_part2() {
-        let input = "depth: 510\ntarget: 10,10";
-        assert_eq!(part2(input), 45);
+        let (depth, target) = parse_input(EXAMPLES[0]).unwrap();
+        assert_eq!(part2(depth, target), 45);
     }
 }
@@ -1,85 +1,106 @@
-use aoc2018::read_input;
+use crate::error::{parse_at, AocError, Result};
+use crate::geom::{BoundingBox, ManhattanBall, Point3};
+use crate::parse::captures;
+use crate::{debug, trace, Answer, Solution};
 use std::collections::BinaryHeap;
 use std::cmp::Reverse;
 
-#[derive(Debug, Clone, Copy)]
-struct Nanobot {
-    x: i64,
-    y: i64,
-    z: i64,
-    r: i64,
-}
+pub struct Day23;
 
-impl Nanobot {
-    fn manhattan_distance(&self, x: i64, y: i64, z: i64) -> i64 {
-        (self.x - x).abs() + (self.y - y).abs() + (self.z - z).abs()
+/// The examples from the puzzle description, for `aoc run --example N`
+const EXAMPLES: &[&str] = &[
+    "\
+pos=<0,0,0>, r=4
+pos=<1,0,0>, r=1
+pos=<4,0,0>, r=3
+pos=<0,2,0>, r=1
+pos=<0,5,0>, r=3
+pos=<0,0,3>, r=1
+pos=<1,1,1>, r=1
+pos=<1,1,2>, r=1
+pos=<1,3,1>, r=1",
+    "\
+pos=<10,12,12>, r=2
+pos=<12,14,12>, r=2
+pos=<16,12,12>, r=4
+pos=<14,14,14>, r=6
+pos=<50,50,50>, r=200
+pos=<10,10,10>, r=5",
+];
+
+impl Solution for Day23 {
+    type Input = Vec<Nanobot>;
+
+    fn parse(&self, input: &str) -> Result<Vec<Nanobot>> {
+        parse_input(input)
     }
-    
-    fn in_range(&self, other: &Nanobot) -> bool {
-        self.manhattan_distance(other.x, other.y, other.z) <= self.r
+
+    fn part1(&self, nanobots: &Vec<Nanobot>) -> Answer {
+        part1(nanobots).into()
+    }
+
+    fn part2(&self, nanobots: &Vec<Nanobot>) -> Answer {
+        part2(nanobots).into()
+    }
+
+    fn examples(&self) -> &'static [&'static str] {
+        EXAMPLES
     }
 }
 
-fn parse_input(input: &str) -> Vec<Nanobot> {
-    input
+/// A nanobot and its signal range
+pub type Nanobot = ManhattanBall<3>;
+
+fn parse_input(input: &str) -> Result<Vec<Nanobot>> {
+    let nanobots: Vec<Nanobot> = input
         .lines()
+        .filter(|line| !line.trim().is_empty())
         .map(|line| {
-            // pos=<x,y,z>, r=radius
-            let parts: Vec<&str> = line.split(", ").collect();
-            let pos = parts[0].strip_prefix("pos=<").unwrap().strip_suffix(">").unwrap();
-            let coords: Vec<i64> = pos.split(',').map(|s| s.parse().unwrap()).collect();
-            let r = parts[1].strip_prefix("r=").unwrap().parse().unwrap();
-            
-            Nanobot {
-                x: coords[0],
-                y: coords[1],
-                z: coords[2],
-                r,
-            }
+            let [x, y, z, r] = captures(input, line, "pos=<{},{},{}>, r={}")?;
+            let center = Point3::new([parse_at(input, x)?, parse_at(input, y)?, parse_at(input, z)?]);
+            Ok(Nanobot::new(center, parse_at(input, r)?))
         })
-        .collect()
+        .collect::<Result<_>>()?;
+
+    if nanobots.is_empty() {
+        return Err(AocError::at(input, input, "no nanobots in input"));
+    }
+    Ok(nanobots)
 }
 
-fn part1(input: &str) -> usize {
-    let nanobots = parse_input(input);
-    
+fn part1(nanobots: &[Nanobot]) -> usize {    
     // Find the nanobot with the largest signal radius
-    let strongest = nanobots.iter().max_by_key(|bot| bot.r).unwrap();
+    let strongest = nanobots.iter().max_by_key(|bot| bot.radius).unwrap();
     
     // Count how many nanobots are in range of the strongest
-    nanobots.iter().filter(|bot| strongest.in_range(bot)).count()
+    nanobots.iter().filter(|bot| strongest.contains(&bot.center)).count()
 }
 
-fn part2(input: &str) -> i64 {
-    let nanobots = parse_input(input);
-    
+fn part2(nanobots: &[Nanobot]) -> i64 {    
     // Find the bounding box
-    let min_x = nanobots.iter().map(|bot| bot.x).min().unwrap();
-    let max_x = nanobots.iter().map(|bot| bot.x).max().unwrap();
-    let min_y = nanobots.iter().map(|bot| bot.y).min().unwrap();
-    let max_y = nanobots.iter().map(|bot| bot.y).max().unwrap();
-    let min_z = nanobots.iter().map(|bot| bot.z).min().unwrap();
-    let max_z = nanobots.iter().map(|bot| bot.z).max().unwrap();
+    let bounds = BoundingBox::from_points(nanobots.iter().map(|bot| bot.center)).unwrap();
     
     // Start with a large cube and subdivide
     let mut best_count = 0;
     let mut best_distance = i64::MAX;
     
-    // Priority queue: (-count, distance, size, x, y, z)
-    let mut queue: BinaryHeap<(i64, i64, i64, i64, i64, i64)> = BinaryHeap::new();
+    // Priority queue: (count, distance, size, corner)
+    let mut queue: BinaryHeap<(usize, i64, i64, Point3)> = BinaryHeap::new();
     
-    let size = (max_x - min_x).max(max_y - min_y).max(max_z - min_z);
-    let init_count = count_in_range(&nanobots, min_x, min_y, min_z, size);
-    queue.push((init_count, 0, size, min_x, min_y, min_z));
+    let size = (bounds.max - bounds.min).0.into_iter().max().unwrap();
+    let init_count = count_in_range(nanobots, bounds.min, size);
+    queue.push((init_count, 0, size, bounds.min));
     
-    while let Some((count, _, size, x, y, z)) = queue.pop() {
+    while let Some((count, _, size, corner)) = queue.pop() {
         if count < best_count {
             continue;
         }
         
         if size == 0 {
-            let dist = x.abs() + y.abs() + z.abs();
+            let dist = corner.manhattan(&Point3::ORIGIN);
+            trace!("point {} is in range of {} nanobots", corner, count);
             if count > best_count || (count == best_count && dist < best_distance) {
+                debug!("new best {}: {} nanobots, distance {}", corner, count, dist);
                 best_count = count;
                 best_distance = dist;
             }
@@ -91,15 +112,13 @@ fn part2(input: &str) -> i64 {
         for dx in 0..=1 {
             for dy in 0..=1 {
                 for dz in 0..=1 {
-                    let nx = x + dx * new_size;
-                    let ny = y + dy * new_size;
-                    let nz = z + dz * new_size;
+                    let n_corner = corner + Point3::new([dx, dy, dz]) * new_size;
                     
-                    let n_count = count_in_range(&nanobots, nx, ny, nz, new_size);
+                    let n_count = count_in_range(nanobots, n_corner, new_size);
                     
                     if n_count >= best_count {
-                        let dist = nx.abs() + ny.abs() + nz.abs();
-                        queue.push((n_count, Reverse(dist).0, new_size, nx, ny, nz));
+                        let dist = n_corner.manhattan(&Point3::ORIGIN);
+                        queue.push((n_count, Reverse(dist).0, new_size, n_corner));
                     }
                 }
             }
@@ -109,46 +128,10 @@ fn part2(input: &str) -> i64 {
     best_distance
 }
 
-fn count_in_range(nanobots: &[Nanobot], x: i64, y: i64, z: i64, size: i64) -> i64 {
-    let mut count = 0;
-    for bot in nanobots {
-        // Check if the cube intersects with the nanobot's range
-        let dx = if x > bot.x {
-            x - bot.x
-        } else if x + size < bot.x {
-            bot.x - (x + size)
-        } else {
-            0
-        };
-        
-        let dy = if y > bot.y {
-            y - bot.y
-        } else if y + size < bot.y {
-            bot.y - (y + size)
-        } else {
-            0
-        };
-        
-        let dz = if z > bot.z {
-            z - bot.z
-        } else if z + size < bot.z {
-            bot.z - (z + size)
-        } else {
-            0
-        };
-        
-        if dx + dy + dz <= bot.r {
-            count += 1;
-        }
-    }
-    count
-}
-
-fn main() {
-    let input = read_input(23);
-
-    println!("Part 1: {}", part1(&input));
-    println!("Part 2: {}", part2(&input));
+/// Number of nanobots in range of any point of the cube at `corner` with sides `size`
+fn count_in_range(nanobots: &[Nanobot], corner: Point3, size: i64) -> usize {
+    let cube = BoundingBox::new(corner, corner + Point3::new([size; 3]));
+    nanobots.iter().filter(|bot| bot.intersects(&cube)).count()
 }
 
 #[cfg(test)]
@@ -157,26 +140,11 @@ mod tests {
 
     #[test]
     fn test_part1() {
-        let input = "pos=<0,0,0>, r=4
-pos=<1,0,0>, r=1
-pos=<4,0,0>, r=3
-pos=<0,2,0>, r=1
-pos=<0,5,0>, r=3
-pos=<0,0,3>, r=1
-pos=<1,1,1>, r=1
-pos=<1,1,2>, r=1
-pos=<1,3,1>, r=1";
-        assert_eq!(part1(input), 7);
+        assert_eq!(part1(&parse_input(EXAMPLES[0]).unwrap()), 7);
     }
 
     #[test]
     fn test_part2() {
-        let input = "pos=<10,12,12>, r=2
-pos=<12,14,12>, r=2
-pos=<16,12,12>, r=4
-pos=<14,14,14>, r=6
-pos=<50,50,50>, r=200
-pos=<10,10,10>, r=5";
-        assert_eq!(part2(input), 36);
+        assert_eq!(part2(&parse_input(EXAMPLES[1]).unwrap()), 36);
     }
 }
@@ -1,9 +1,44 @@
-use aoc2018::read_input;
+use crate::error::{parse_at, AocError, Result};
+use crate::parse::captures;
+use crate::{debug, trace, Answer, Solution};
 use std::collections::HashSet;
 
+pub struct Day24;
+
+/// The examples from the puzzle description, for `aoc run --example N`
+const EXAMPLES: &[&str] = &[
+    "\
+Immune System:
+17 units each with 5390 hit points (weak to radiation, bludgeoning) with an attack that does 4507 fire damage at initiative 2
+989 units each with 1274 hit points (immune to fire; weak to bludgeoning, slashing) with an attack that does 25 slashing damage at initiative 3
+
+Infection:
+801 units each with 4706 hit points (weak to radiation) with an attack that does 116 bludgeoning damage at initiative 1
+4485 units each with 2961 hit points (immune to radiation; weak to fire, cold) with an attack that does 12 slashing damage at initiative 4",
+];
+
+impl Solution for Day24 {
+    type Input = Vec<Group>;
+
+    fn parse(&self, input: &str) -> Result<Vec<Group>> {
+        parse_input(input)
+    }
+
+    fn part1(&self, groups: &Vec<Group>) -> Answer {
+        part1(groups).into()
+    }
+
+    fn part2(&self, groups: &Vec<Group>) -> Answer {
+        part2(groups).into()
+    }
+
+    fn examples(&self) -> &'static [&'static str] {
+        EXAMPLES
+    }
+}
+
 #[derive(Debug, Clone)]
-struct Group {
-    id: usize,
+pub struct Group {
     army: Army,
     units: i32,
     hp: i32,
@@ -36,10 +71,9 @@ impl Group {
     }
 }
 
-fn parse_input(input: &str) -> Vec<Group> {
+fn parse_input(input: &str) -> Result<Vec<Group>> {
     let mut groups = Vec::new();
     let mut current_army = Army::ImmuneSystem;
-    let mut id = 0;
     
     for line in input.lines() {
         if line.trim().is_empty() {
@@ -50,36 +84,40 @@ fn parse_input(input: &str) -> Vec<Group> {
             current_army = Army::Infection;
         } else {
             // Parse group line
-            let mut weaknesses = Vec::new();
-            let mut immunities = Vec::new();
+            let [units, hp_field, special, attack_damage, attack_type, initiative] = captures(
+                input,
+                line,
+                "{} units each with {} hit points{}with an attack that does {} {} damage at initiative {}",
+            )?;
+            let units: i32 = parse_at(input, units)?;
+            let hp: i32 = parse_at(input, hp_field)?;
+            if hp <= 0 {
+                return Err(AocError::at(input, hp_field, "hit points must be positive"));
+            }
+            let attack_damage: i32 = parse_at(input, attack_damage)?;
+            let attack_type = attack_type.to_string();
+            let initiative: i32 = parse_at(input, initiative)?;
             
             // Extract special properties if present
-            if let Some(start) = line.find('(') {
-                if let Some(end) = line.find(')') {
-                    let special = &line[start + 1..end];
-                    for part in special.split("; ") {
-                        if let Some(stripped) = part.strip_prefix("weak to ") {
-                            weaknesses = stripped.split(", ").map(|s| s.to_string()).collect();
-                        } else if let Some(stripped) = part.strip_prefix("immune to ") {
-                            immunities = stripped.split(", ").map(|s| s.to_string()).collect();
-                        }
+            let mut weaknesses = Vec::new();
+            let mut immunities = Vec::new();
+            if !special.is_empty() {
+                let special = special
+                    .strip_prefix('(')
+                    .and_then(|special| special.strip_suffix(')'))
+                    .ok_or_else(|| AocError::at(input, special, "expected `(weak to ...; immune to ...)`"))?;
+                for part in special.split("; ") {
+                    if let Some(stripped) = part.strip_prefix("weak to ") {
+                        weaknesses = stripped.split(", ").map(|s| s.to_string()).collect();
+                    } else if let Some(stripped) = part.strip_prefix("immune to ") {
+                        immunities = stripped.split(", ").map(|s| s.to_string()).collect();
+                    } else {
+                        return Err(AocError::at(input, part, "expected `weak to ...` or `immune to ...`"));
                     }
                 }
             }
             
-            // Parse the main properties
-            let parts: Vec<&str> = line.split_whitespace().collect();
-            let units: i32 = parts[0].parse().unwrap();
-            let hp: i32 = parts[4].parse().unwrap();
-            
-            // Find "does" keyword to locate attack damage
-            let does_idx = parts.iter().position(|&s| s == "does").unwrap();
-            let attack_damage: i32 = parts[does_idx + 1].parse().unwrap();
-            let attack_type = parts[does_idx + 2].to_string();
-            let initiative: i32 = parts[parts.len() - 1].parse().unwrap();
-            
             groups.push(Group {
-                id,
                 army: current_army,
                 units,
                 hp,
@@ -89,11 +127,10 @@ fn parse_input(input: &str) -> Vec<Group> {
                 weaknesses,
                 immunities,
             });
-            id += 1;
         }
     }
     
-    groups
+    Ok(groups)
 }
 
 fn simulate_combat(mut groups: Vec<Group>, boost: i32) -> Option<(Army, i32)> {
@@ -114,10 +151,12 @@ fn simulate_combat(mut groups: Vec<Group>, boost: i32) -> Option<(Army, i32)> {
         
         if !immune_alive {
             let total = groups.iter().map(|g| g.units).sum();
+            debug!("boost {}: infection wins with {} units", boost, total);
             return Some((Army::Infection, total));
         }
         if !infection_alive {
             let total = groups.iter().map(|g| g.units).sum();
+            debug!("boost {}: immune system wins with {} units", boost, total);
             return Some((Army::ImmuneSystem, total));
         }
         
@@ -190,27 +229,31 @@ fn simulate_combat(mut groups: Vec<Group>, boost: i32) -> Option<(Army, i32)> {
             groups[defender_idx].units = (groups[defender_idx].units - units_killed).max(0);
         }
         
+        trace!(
+            "round done: {} immune system and {} infection units left",
+            groups.iter().filter(|g| g.army == Army::ImmuneSystem).map(|g| g.units).sum::<i32>(),
+            groups.iter().filter(|g| g.army == Army::Infection).map(|g| g.units).sum::<i32>()
+        );
+
         // Check for stalemate
         if !any_killed {
+            debug!("boost {}: stalemate", boost);
             return None;
         }
     }
 }
 
-fn part1(input: &str) -> i32 {
-    let groups = parse_input(input);
-    if let Some((_, units)) = simulate_combat(groups, 0) {
+fn part1(groups: &[Group]) -> i32 {
+    if let Some((_, units)) = simulate_combat(groups.to_vec(), 0) {
         units
     } else {
         0
     }
 }
 
-fn part2(input: &str) -> i32 {
-    let groups = parse_input(input);
-    
+fn part2(groups: &[Group]) -> i32 {    
     for boost in 1.. {
-        if let Some((winner, units)) = simulate_combat(groups.clone(), boost) {
+        if let Some((winner, units)) = simulate_combat(groups.to_vec(), boost) {
             if winner == Army::ImmuneSystem {
                 return units;
             }
@@ -220,38 +263,25 @@ fn part2(input: &str) -> i32 {
     0
 }
 
-fn main() {
-    let input = read_input(24);
-
-    println!("Part 1: {}", part1(&input));
-    println!("Part 2: {}", part2(&input));
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
 
     #[test]
     fn test_part1() {
-        let input = "Immune System:
-17 units each with 5390 hit points (weak to radiation, bludgeoning) with an attack that does 4507 fire damage at initiative 2
-989 units each with 1274 hit points (immune to fire; weak to bludgeoning, slashing) with an attack that does 25 slashing damage at initiative 3
-
-Infection:
-801 units each with 4706 hit points (weak to radiation) with an attack that does 116 bludgeoning damage at initiative 1
-4485 units each with 2961 hit points (immune to radiation; weak to fire, cold) with an attack that does 12 slashing damage at initiative 4";
-        assert_eq!(part1(input), 5216);
+        assert_eq!(part1(&parse_input(EXAMPLES[0]).unwrap()), 5216);
     }
 
     #[test]
     fn test_part2() {
-        let input = "Immune System:
-17 units each with 5390 hit points (weak to radiation, bludgeoning) with an attack that does 4507 fire damage at initiative 2
-989 units each with 1274 hit points (immune to fire; weak to bludgeoning, slashing) with an attack that does 25 slashing damage at initiative 3
+        assert_eq!(part2(&parse_input(EXAMPLES[0]).unwrap()), 51);
+    }
 
-Infection:
-801 units each with 4706 hit points (weak to radiation) with an attack that does 116 bludgeoning damage at initiative 1
-4485 units each with 2961 hit points (immune to radiation; weak to fire, cold) with an attack that does 12 slashing damage at initiative 4";
-        assert_eq!(part2(input), 51);
+    #[test]
+    fn test_parse_errors() {
+        let truncated = "Immune System:\n17 units each with 5390 hit points with an attack that does 4507 fire damage\n";
+        assert!(matches!(parse_input(truncated), Err(AocError::Parse { line: 2, column: 66, .. })));
+        let special = "Infection:\n17 units each with 5390 hit points (strong to fire) with an attack that does 4507 fire damage at initiative 2\n";
+        assert!(matches!(parse_input(special), Err(AocError::Parse { line: 2, column: 37, .. })));
     }
 }
@@ -1,19 +1,72 @@
-use aoc2018::read_input;
-
-#[derive(Debug, Clone, Copy)]
-struct Point {
-    x: i32,
-    y: i32,
-    z: i32,
-    w: i32,
-}
+use crate::error::Result;
+use crate::geom::Point4;
+use crate::parse::exact_ints;
+use crate::{debug, Answer, Solution};
+
+pub struct Day25;
+
+/// The examples from the puzzle description, for `aoc run --example N`
+const EXAMPLES: &[&str] = &[
+    " 0,0,0,0
+ 3,0,0,0
+ 0,3,0,0
+ 0,0,3,0
+ 0,0,0,3
+ 0,0,0,6
+ 9,0,0,0
+12,0,0,0",
+    "\
+-1,2,2,0
+0,0,2,-2
+0,0,0,-2
+-1,2,0,0
+-2,-2,-2,2
+3,0,2,-1
+-1,3,2,2
+-1,0,-1,0
+0,2,1,-2
+3,0,0,0",
+    "\
+1,-1,0,1
+2,0,-1,0
+3,2,-1,0
+0,0,3,1
+0,0,-1,-1
+2,3,-2,0
+-2,2,0,0
+2,-2,0,-1
+1,-1,0,-1
+3,2,0,2",
+    "\
+1,-1,-1,-2
+-2,-2,0,1
+0,2,1,3
+-2,3,-2,1
+0,2,3,-2
+-1,-1,1,-2
+0,-2,-1,0
+-2,2,3,-1
+1,2,2,0
+-1,-2,0,-2",
+];
+
+impl Solution for Day25 {
+    type Input = Vec<Point4>;
+
+    fn parse(&self, input: &str) -> Result<Vec<Point4>> {
+        parse_input(input)
+    }
+
+    fn part1(&self, points: &Vec<Point4>) -> Answer {
+        part1(points).into()
+    }
 
-impl Point {
-    fn manhattan_distance(&self, other: &Point) -> i32 {
-        (self.x - other.x).abs()
-            + (self.y - other.y).abs()
-            + (self.z - other.z).abs()
-            + (self.w - other.w).abs()
+    fn part2(&self, _points: &Vec<Point4>) -> Answer {
+        "Merry Christmas!".into()
+    }
+
+    fn examples(&self) -> &'static [&'static str] {
+        EXAMPLES
     }
 }
 
@@ -61,46 +114,29 @@ impl UnionFind {
     }
 }
 
-fn parse_input(input: &str) -> Vec<Point> {
+fn parse_input(input: &str) -> Result<Vec<Point4>> {
     input
         .lines()
-        .map(|line| {
-            let parts: Vec<i32> = line.trim().split(',').map(|s| s.trim().parse().unwrap()).collect();
-            Point {
-                x: parts[0],
-                y: parts[1],
-                z: parts[2],
-                w: parts[3],
-            }
-        })
+        .filter(|line| !line.trim().is_empty())
+        .map(|line| Ok(Point4::new(exact_ints(input, line)?)))
         .collect()
 }
 
-fn part1(input: &str) -> usize {
-    let points = parse_input(input);
+fn part1(points: &[Point4]) -> usize {
     let n = points.len();
     let mut uf = UnionFind::new(n);
 
     for i in 0..n {
         for j in i + 1..n {
-            if points[i].manhattan_distance(&points[j]) <= 3 {
+            if points[i].manhattan(&points[j]) <= 3 {
                 uf.union(i, j);
             }
         }
     }
 
-    uf.count_sets()
-}
-
-fn part2(_input: &str) -> &str {
-    "Merry Christmas!"
-}
-
-fn main() {
-    let input = read_input(25);
-
-    println!("Part 1: {}", part1(&input));
-    println!("Part 2: {}", part2(&input));
+    let constellations = uf.count_sets();
+    debug!("{} points form {} constellations", n, constellations);
+    constellations
 }
 
 #[cfg(test)]
@@ -109,59 +145,21 @@ mod tests {
 
     #[test]
     fn test_part1_example1() {
-        let input = " 0,0,0,0
- 3,0,0,0
- 0,3,0,0
- 0,0,3,0
- 0,0,0,3
- 0,0,0,6
- 9,0,0,0
-12,0,0,0";
-        assert_eq!(part1(input), 2);
+        assert_eq!(part1(&parse_input(EXAMPLES[0]).unwrap()), 2);
     }
 
     #[test]
     fn test_part1_example2() {
-        let input = "-1,2,2,0
-0,0,2,-2
-0,0,0,-2
--1,2,0,0
--2,-2,-2,2
-3,0,2,-1
--1,3,2,2
--1,0,-1,0
-0,2,1,-2
-3,0,0,0";
-        assert_eq!(part1(input), 4);
+        assert_eq!(part1(&parse_input(EXAMPLES[1]).unwrap()), 4);
     }
 
     #[test]
     fn test_part1_example3() {
-        let input = "1,-1,0,1
-2,0,-1,0
-3,2,-1,0
-0,0,3,1
-0,0,-1,-1
-2,3,-2,0
--2,2,0,0
-2,-2,0,-1
-1,-1,0,-1
-3,2,0,2";
-        assert_eq!(part1(input), 3);
+        assert_eq!(part1(&parse_input(EXAMPLES[2]).unwrap()), 3);
     }
 
     #[test]
     fn test_part1_example4() {
-        let input = "1,-1,-1,-2
--2,-2,0,1
-0,2,1,3
--2,3,-2,1
-0,2,3,-2
--1,-1,1,-2
-0,-2,-1,0
--2,2,3,-1
-1,2,2,0
--1,-2,0,-2";
-        assert_eq!(part1(input), 8);
+        assert_eq!(part1(&parse_input(EXAMPLES[3]).unwrap()), 8);
     }
 }
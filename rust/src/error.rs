@@ -0,0 +1,142 @@
+use std::error::Error;
+use std::fmt;
+use std::fmt::Display;
+use std::io;
+use std::path::PathBuf;
+use std::str::FromStr;
+
+/// Errors raised while loading settings or loading and parsing puzzle input
+#[derive(Debug)]
+pub enum AocError {
+    MissingInput { path: PathBuf },
+    Io { path: PathBuf, source: io::Error },
+    /// `line` and `column` are 1-based; 0 means the position is unknown
+    Parse { line: usize, column: usize, message: String },
+    /// A required setting was found in none of the `checked` sources
+    MissingSetting { name: &'static str, checked: Vec<String> },
+    InvalidSetting { name: &'static str, message: String },
+    /// `--example` asked for an example the day doesn't have
+    MissingExample { day: u8, example: usize, available: usize },
+}
+
+pub type Result<T> = std::result::Result<T, AocError>;
+
+impl AocError {
+    /// Wrap an I/O error for `path`, treating "not found" as a missing input
+    pub fn io(path: impl Into<PathBuf>, source: io::Error) -> Self {
+        let path = path.into();
+        if source.kind() == io::ErrorKind::NotFound {
+            AocError::MissingInput { path }
+        } else {
+            AocError::Io { path, source }
+        }
+    }
+
+    /// Parse error pointing at `part`, which must be a slice of `input`
+    pub fn at(input: &str, part: &str, message: impl Into<String>) -> Self {
+        let (line, column) = locate(input, part).unwrap_or((0, 0));
+        AocError::Parse {
+            line,
+            column,
+            message: message.into(),
+        }
+    }
+}
+
+impl fmt::Display for AocError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            AocError::MissingInput { path } => write!(
+                f,
+                "Input file {} not found (download it with `cargo run --bin prepare <day>`)",
+                path.display()
+            ),
+            AocError::Io { path, source } => {
+                write!(f, "Failed to read input file {}: {}", path.display(), source)
+            }
+            AocError::Parse { line: 0, message, .. } => write!(f, "Parse error: {}", message),
+            AocError::Parse {
+                line,
+                column,
+                message,
+            } => write!(f, "Parse error at line {}, column {}: {}", line, column, message),
+            AocError::MissingSetting { name, checked } => {
+                write!(f, "{} is not set. Checked, in order:", name)?;
+                for source in checked {
+                    write!(f, "\n  - {}", source)?;
+                }
+                Ok(())
+            }
+            AocError::InvalidSetting { name, message } => write!(f, "Invalid {}: {}", name, message),
+            AocError::MissingExample {
+                day,
+                example,
+                available,
+            } => write!(f, "Day {} has no example {} ({} available)", day, example, available),
+        }
+    }
+}
+
+impl Error for AocError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            AocError::Io { source, .. } => Some(source),
+            _ => None,
+        }
+    }
+}
+
+/// 1-based line and column of `part` within `input`, if it is a slice of it
+pub fn locate(input: &str, part: &str) -> Option<(usize, usize)> {
+    let start = input.as_ptr() as usize;
+    let offset = (part.as_ptr() as usize).checked_sub(start)?;
+    if offset > input.len() {
+        return None;
+    }
+
+    let before = &input[..offset];
+    let line = before.matches('\n').count() + 1;
+    let line_start = before.rfind('\n').map_or(0, |i| i + 1);
+    let column = before[line_start..].chars().count() + 1;
+    Some((line, column))
+}
+
+/// Parse `field`, a slice of `input`, reporting its position on failure
+pub fn parse_at<T>(input: &str, field: &str) -> Result<T>
+where
+    T: FromStr,
+    T::Err: Display,
+{
+    field
+        .parse()
+        .map_err(|e| AocError::at(input, field, format!("cannot parse {:?}: {}", field, e)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_locate() {
+        let input = "abc\ndef ghi\n";
+        assert_eq!(locate(input, &input[0..1]), Some((1, 1)));
+        assert_eq!(locate(input, &input[8..11]), Some((2, 5)));
+        assert_eq!(locate(input, "elsewhere"), None);
+    }
+
+    #[test]
+    fn test_parse_at_reports_position() {
+        let input = "1 2\n3 x4";
+        let field = input.split_whitespace().nth(3).unwrap();
+        let err = parse_at::<i32>(input, field).unwrap_err();
+        assert!(matches!(err, AocError::Parse { line: 2, column: 3, .. }));
+        assert!(err.to_string().starts_with("Parse error at line 2, column 3: cannot parse \"x4\""));
+    }
+
+    #[test]
+    fn test_io_not_found_is_missing_input() {
+        let err = AocError::io("inputs/day99.txt", io::Error::from(io::ErrorKind::NotFound));
+        assert!(matches!(err, AocError::MissingInput { .. }));
+        assert!(err.to_string().contains("inputs/day99.txt"));
+    }
+}
@@ -0,0 +1,268 @@
+//! Integer points in any number of dimensions, with Manhattan and Chebyshev
+//! distances, bounding boxes and Manhattan balls
+//!
+//! Coordinates are `i64` and bounding boxes are inclusive on both ends, so a
+//! box from a point to itself contains exactly that point.
+
+use std::fmt;
+use std::ops::{Add, AddAssign, Index, IndexMut, Mul, Neg, Sub, SubAssign};
+
+/// A point (or offset) with `N` integer coordinates
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Point<const N: usize>(pub [i64; N]);
+
+pub type Point2 = Point<2>;
+pub type Point3 = Point<3>;
+pub type Point4 = Point<4>;
+
+impl<const N: usize> Point<N> {
+    pub const ORIGIN: Self = Point([0; N]);
+
+    pub fn new(coords: [i64; N]) -> Self {
+        Point(coords)
+    }
+
+    /// Sum of the absolute coordinate differences
+    pub fn manhattan(&self, other: &Self) -> i64 {
+        (0..N).map(|i| (self.0[i] - other.0[i]).abs()).sum()
+    }
+
+    /// Largest absolute coordinate difference
+    pub fn chebyshev(&self, other: &Self) -> i64 {
+        (0..N).map(|i| (self.0[i] - other.0[i]).abs()).max().unwrap_or(0)
+    }
+
+    /// Apply `f` to each coordinate pair of `self` and `other`
+    pub fn zip(&self, other: &Self, mut f: impl FnMut(i64, i64) -> i64) -> Self {
+        Point(std::array::from_fn(|i| f(self.0[i], other.0[i])))
+    }
+}
+
+impl<const N: usize> fmt::Display for Point<N> {
+    /// Coordinates separated by commas, as puzzle answers write them
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for (i, coord) in self.0.iter().enumerate() {
+            if i > 0 {
+                write!(f, ",")?;
+            }
+            write!(f, "{}", coord)?;
+        }
+        Ok(())
+    }
+}
+
+impl<const N: usize> Index<usize> for Point<N> {
+    type Output = i64;
+
+    fn index(&self, axis: usize) -> &i64 {
+        &self.0[axis]
+    }
+}
+
+impl<const N: usize> IndexMut<usize> for Point<N> {
+    fn index_mut(&mut self, axis: usize) -> &mut i64 {
+        &mut self.0[axis]
+    }
+}
+
+impl<const N: usize> Add for Point<N> {
+    type Output = Self;
+
+    fn add(self, other: Self) -> Self {
+        self.zip(&other, |a, b| a + b)
+    }
+}
+
+impl<const N: usize> Sub for Point<N> {
+    type Output = Self;
+
+    fn sub(self, other: Self) -> Self {
+        self.zip(&other, |a, b| a - b)
+    }
+}
+
+impl<const N: usize> AddAssign for Point<N> {
+    fn add_assign(&mut self, other: Self) {
+        *self = *self + other;
+    }
+}
+
+impl<const N: usize> SubAssign for Point<N> {
+    fn sub_assign(&mut self, other: Self) {
+        *self = *self - other;
+    }
+}
+
+impl<const N: usize> Mul<i64> for Point<N> {
+    type Output = Self;
+
+    fn mul(self, factor: i64) -> Self {
+        Point(self.0.map(|coord| coord * factor))
+    }
+}
+
+impl<const N: usize> Neg for Point<N> {
+    type Output = Self;
+
+    fn neg(self) -> Self {
+        self * -1
+    }
+}
+
+/// An axis-aligned box from `min` to `max`, both inclusive
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct BoundingBox<const N: usize> {
+    pub min: Point<N>,
+    pub max: Point<N>,
+}
+
+impl<const N: usize> BoundingBox<N> {
+    pub fn new(min: Point<N>, max: Point<N>) -> Self {
+        BoundingBox { min, max }
+    }
+
+    /// The smallest box containing all `points`, or `None` if there are none
+    pub fn from_points(points: impl IntoIterator<Item = Point<N>>) -> Option<Self> {
+        let mut points = points.into_iter();
+        let first = points.next()?;
+        let mut bounds = BoundingBox::new(first, first);
+        for point in points {
+            bounds.expand(point);
+        }
+        Some(bounds)
+    }
+
+    /// Grow the box to contain `point`
+    pub fn expand(&mut self, point: Point<N>) {
+        self.min = self.min.zip(&point, i64::min);
+        self.max = self.max.zip(&point, i64::max);
+    }
+
+    /// The box grown by `margin` on every side
+    pub fn padded(&self, margin: i64) -> Self {
+        let margin = Point([margin; N]);
+        BoundingBox::new(self.min - margin, self.max + margin)
+    }
+
+    pub fn contains(&self, point: &Point<N>) -> bool {
+        (0..N).all(|i| self.min[i] <= point[i] && point[i] <= self.max[i])
+    }
+
+    /// Whether `point` lies on the outer faces of the box
+    pub fn on_boundary(&self, point: &Point<N>) -> bool {
+        self.contains(point) && (0..N).any(|i| point[i] == self.min[i] || point[i] == self.max[i])
+    }
+
+    /// Number of integer points along each axis
+    pub fn size(&self) -> Point<N> {
+        self.max - self.min + Point([1; N])
+    }
+
+    /// Number of integer points in the box
+    pub fn area(&self) -> i64 {
+        self.size().0.iter().product()
+    }
+
+    /// Manhattan distance from `point` to the nearest point of the box
+    pub fn manhattan_to(&self, point: &Point<N>) -> i64 {
+        (0..N)
+            .map(|i| (self.min[i] - point[i]).max(0) + (point[i] - self.max[i]).max(0))
+            .sum()
+    }
+}
+
+impl BoundingBox<2> {
+    /// All points of the box in reading order (by row, then column)
+    pub fn points(&self) -> impl Iterator<Item = Point2> {
+        let (min, max) = (self.min, self.max);
+        (min[1]..=max[1]).flat_map(move |y| (min[0]..=max[0]).map(move |x| Point([x, y])))
+    }
+}
+
+/// All points within Manhattan distance `radius` of `center`; an octahedron in
+/// 3D
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ManhattanBall<const N: usize> {
+    pub center: Point<N>,
+    pub radius: i64,
+}
+
+impl<const N: usize> ManhattanBall<N> {
+    pub fn new(center: Point<N>, radius: i64) -> Self {
+        ManhattanBall { center, radius }
+    }
+
+    pub fn contains(&self, point: &Point<N>) -> bool {
+        self.center.manhattan(point) <= self.radius
+    }
+
+    /// Whether the ball and `bounds` share at least one point
+    pub fn intersects(&self, bounds: &BoundingBox<N>) -> bool {
+        bounds.manhattan_to(&self.center) <= self.radius
+    }
+
+    /// The smallest box containing the ball
+    pub fn bounding_box(&self) -> BoundingBox<N> {
+        BoundingBox::new(self.center, self.center).padded(self.radius)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_arithmetic_and_distances() {
+        let a = Point::new([1, -2, 3]);
+        let b = Point::new([4, 2, 3]);
+        assert_eq!(a + b, Point([5, 0, 6]));
+        assert_eq!(b - a, Point([3, 4, 0]));
+        assert_eq!(-a * 2, Point([-2, 4, -6]));
+        assert_eq!(a.manhattan(&b), 7);
+        assert_eq!(a.chebyshev(&b), 4);
+        assert_eq!(Point3::ORIGIN.manhattan(&a), 6);
+        assert_eq!(a.to_string(), "1,-2,3");
+
+        let mut c = a;
+        c += b;
+        c -= Point([1, 1, 1]);
+        assert_eq!(c, Point([4, -1, 5]));
+    }
+
+    #[test]
+    fn test_bounding_box() {
+        let bounds = BoundingBox::from_points([Point([3, 1]), Point([1, 4]), Point([2, 2])]).unwrap();
+        assert_eq!(bounds, BoundingBox::new(Point([1, 1]), Point([3, 4])));
+        assert_eq!(bounds.size(), Point([3, 4]));
+        assert_eq!(bounds.area(), 12);
+        assert!(bounds.contains(&Point([3, 4])));
+        assert!(!bounds.contains(&Point([0, 2])));
+        assert!(bounds.on_boundary(&Point([1, 2])));
+        assert!(!bounds.on_boundary(&Point([2, 2])));
+        assert_eq!(bounds.manhattan_to(&Point([5, 0])), 3);
+        assert_eq!(bounds.points().count(), 12);
+        assert_eq!(bounds.points().nth(3), Some(Point([1, 2])));
+        assert_eq!(bounds.padded(1).area(), 30);
+        assert!(BoundingBox::<2>::from_points([]).is_none());
+    }
+
+    #[test]
+    fn test_ball_intersects_box() {
+        let ball = ManhattanBall::new(Point([0, 0, 0]), 3);
+        assert!(ball.contains(&Point([1, -1, 1])));
+        assert!(!ball.contains(&Point([2, 2, 0])));
+        assert!(ball.intersects(&BoundingBox::new(Point([2, 1, 0]), Point([5, 5, 5]))));
+        assert!(!ball.intersects(&BoundingBox::new(Point([2, 2, 0]), Point([5, 5, 5]))));
+
+        // Agrees with checking every point of the box
+        let corner = Point([-3, -3, -1]);
+        for size in 0..4 {
+            let cube = BoundingBox::new(corner, corner + Point([size; 3]));
+            let any = (-5..=5).any(|x| {
+                (-5..=5).any(|y| (-5..=5).any(|z| cube.contains(&Point([x, y, z])) && ball.contains(&Point([x, y, z]))))
+            });
+            assert_eq!(ball.intersects(&cube), any);
+        }
+        assert_eq!(ball.bounding_box().area(), 7 * 7 * 7);
+    }
+}
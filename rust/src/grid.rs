@@ -0,0 +1,349 @@
+//! 2D grids: a dense `Grid` for rectangular maps and a `SparseGrid` whose bounds
+//! grow with what is stored in it
+//!
+//! Positions are `(x, y)` with `y` growing downwards, and "reading order" is
+//! top to bottom, then left to right, as in the puzzles.
+
+use crate::error::{AocError, Result};
+use std::collections::HashMap;
+use std::ops::{Index, IndexMut};
+
+/// Offsets of the 4 orthogonal neighbours, in reading order
+pub const NEIGHBOURS_4: [(i64, i64); 4] = [(0, -1), (-1, 0), (1, 0), (0, 1)];
+
+/// Offsets of all 8 neighbours, in reading order
+pub const NEIGHBOURS_8: [(i64, i64); 8] = [(-1, -1), (0, -1), (1, -1), (-1, 0), (1, 0), (-1, 1), (0, 1), (1, 1)];
+
+/// Parse `input` as rows of characters, calling `cell` on each with its position
+///
+/// Characters `cell` rejects are reported with their line and column.
+fn parse_cells<T>(input: &str, mut cell: impl FnMut(char) -> Option<T>, mut add: impl FnMut(usize, usize, T)) -> Result<()> {
+    for (y, line) in input.lines().enumerate() {
+        for (x, (offset, c)) in line.char_indices().enumerate() {
+            let value = cell(c).ok_or_else(|| AocError::at(input, &line[offset..], format!("unexpected character {:?}", c)))?;
+            add(x, y, value);
+        }
+    }
+    Ok(())
+}
+
+/// A rectangular grid stored row by row in one `Vec`
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Grid<T> {
+    width: usize,
+    height: usize,
+    cells: Vec<T>,
+}
+
+impl<T> Grid<T> {
+    pub fn new(width: usize, height: usize, fill: T) -> Self
+    where
+        T: Clone,
+    {
+        Grid {
+            width,
+            height,
+            cells: vec![fill; width * height],
+        }
+    }
+
+    /// Parse one row per line, mapping each character with `cell`
+    ///
+    /// All lines must have the same length.
+    pub fn parse(input: &str, cell: impl FnMut(char) -> Option<T>) -> Result<Self> {
+        let width = input.lines().next().map_or(0, |line| line.chars().count());
+        if let Some(line) = input.lines().find(|line| line.chars().count() != width) {
+            return Err(AocError::at(
+                input,
+                line,
+                format!("row is {} wide, the first row {}", line.chars().count(), width),
+            ));
+        }
+
+        let mut cells = Vec::with_capacity(input.len());
+        parse_cells(input, cell, |_, _, value| cells.push(value))?;
+        Ok(Grid {
+            width,
+            height: input.lines().count(),
+            cells,
+        })
+    }
+
+    /// One line per row, mapping each cell with `cell`
+    pub fn render(&self, mut cell: impl FnMut(&T) -> char) -> String {
+        self.rows()
+            .map(|row| row.iter().map(&mut cell).collect::<String>())
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    /// The position at `(x, y)`, if it lies inside the grid
+    pub fn position(&self, x: i64, y: i64) -> Option<(usize, usize)> {
+        let inside = (0..self.width as i64).contains(&x) && (0..self.height as i64).contains(&y);
+        inside.then_some((x as usize, y as usize))
+    }
+
+    pub fn get(&self, (x, y): (usize, usize)) -> Option<&T> {
+        (x < self.width && y < self.height).then(|| &self.cells[y * self.width + x])
+    }
+
+    pub fn get_mut(&mut self, (x, y): (usize, usize)) -> Option<&mut T> {
+        (x < self.width && y < self.height).then(|| &mut self.cells[y * self.width + x])
+    }
+
+    /// Positions inside the grid at `offsets` from `pos`
+    fn offsets(&self, (x, y): (usize, usize), offsets: &'static [(i64, i64)]) -> impl Iterator<Item = (usize, usize)> + '_ {
+        offsets
+            .iter()
+            .filter_map(move |&(dx, dy)| self.position(x as i64 + dx, y as i64 + dy))
+    }
+
+    /// The orthogonal neighbours of `pos` inside the grid, in reading order
+    pub fn neighbours4(&self, pos: (usize, usize)) -> impl Iterator<Item = (usize, usize)> + '_ {
+        self.offsets(pos, &NEIGHBOURS_4)
+    }
+
+    /// All neighbours of `pos` inside the grid, including diagonals, in reading order
+    pub fn neighbours8(&self, pos: (usize, usize)) -> impl Iterator<Item = (usize, usize)> + '_ {
+        self.offsets(pos, &NEIGHBOURS_8)
+    }
+
+    /// Every position in reading order
+    pub fn positions(&self) -> impl Iterator<Item = (usize, usize)> {
+        let width = self.width;
+        (0..self.width * self.height).map(move |i| (i % width, i / width))
+    }
+
+    /// Every position and cell in reading order
+    pub fn iter(&self) -> impl Iterator<Item = ((usize, usize), &T)> {
+        self.positions().zip(&self.cells)
+    }
+
+    /// The rows from top to bottom
+    pub fn rows(&self) -> impl Iterator<Item = &[T]> {
+        // `max(1)` keeps `chunks` happy for a grid without columns
+        self.cells.chunks(self.width.max(1))
+    }
+
+    /// A grid of the same size with `f` applied to every position and cell
+    pub fn map<U>(&self, mut f: impl FnMut((usize, usize), &T) -> U) -> Grid<U> {
+        Grid {
+            width: self.width,
+            height: self.height,
+            cells: self.iter().map(|(pos, cell)| f(pos, cell)).collect(),
+        }
+    }
+
+    /// How many cells `predicate` holds for
+    pub fn count(&self, mut predicate: impl FnMut(&T) -> bool) -> usize {
+        self.cells.iter().filter(|cell| predicate(cell)).count()
+    }
+}
+
+impl<T> Index<(usize, usize)> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, pos: (usize, usize)) -> &T {
+        self.get(pos)
+            .unwrap_or_else(|| panic!("{:?} is outside the {}x{} grid", pos, self.width, self.height))
+    }
+}
+
+impl<T> IndexMut<(usize, usize)> for Grid<T> {
+    fn index_mut(&mut self, pos: (usize, usize)) -> &mut T {
+        let (width, height) = (self.width, self.height);
+        self.get_mut(pos)
+            .unwrap_or_else(|| panic!("{:?} is outside the {}x{} grid", pos, width, height))
+    }
+}
+
+/// A grid that only stores the cells that were set, on unbounded signed positions
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SparseGrid<T> {
+    cells: HashMap<(i64, i64), T>,
+    /// Smallest and largest corner of everything ever inserted
+    bounds: Option<((i64, i64), (i64, i64))>,
+}
+
+impl<T> Default for SparseGrid<T> {
+    fn default() -> Self {
+        SparseGrid {
+            cells: HashMap::new(),
+            bounds: None,
+        }
+    }
+}
+
+impl<T> SparseGrid<T> {
+    pub fn new() -> Self {
+        SparseGrid::default()
+    }
+
+    /// Parse one row per line from `(0, 0)`; characters `cell` maps to `Ok(None)` are
+    /// left empty
+    pub fn parse(input: &str, mut cell: impl FnMut(char) -> Option<Option<T>>) -> Result<Self> {
+        let mut grid = SparseGrid::new();
+        parse_cells(input, &mut cell, |x, y, value| {
+            if let Some(value) = value {
+                grid.insert((x as i64, y as i64), value);
+            }
+        })?;
+        Ok(grid)
+    }
+
+    /// The bounding box as lines, mapping empty cells to `None`
+    pub fn render(&self, mut cell: impl FnMut(Option<&T>) -> char) -> String {
+        let Some(((min_x, min_y), (max_x, max_y))) = self.bounds else {
+            return String::new();
+        };
+        (min_y..=max_y)
+            .map(|y| (min_x..=max_x).map(|x| cell(self.get((x, y)))).collect::<String>())
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    /// Set a cell, growing the bounds to include it
+    pub fn insert(&mut self, (x, y): (i64, i64), value: T) -> Option<T> {
+        self.bounds = Some(match self.bounds {
+            None => ((x, y), (x, y)),
+            Some(((min_x, min_y), (max_x, max_y))) => ((min_x.min(x), min_y.min(y)), (max_x.max(x), max_y.max(y))),
+        });
+        self.cells.insert((x, y), value)
+    }
+
+    /// Clear a cell; the bounds stay as they are
+    pub fn remove(&mut self, pos: (i64, i64)) -> Option<T> {
+        self.cells.remove(&pos)
+    }
+
+    pub fn get(&self, pos: (i64, i64)) -> Option<&T> {
+        self.cells.get(&pos)
+    }
+
+    pub fn get_mut(&mut self, pos: (i64, i64)) -> Option<&mut T> {
+        self.cells.get_mut(&pos)
+    }
+
+    pub fn contains(&self, pos: (i64, i64)) -> bool {
+        self.cells.contains_key(&pos)
+    }
+
+    pub fn len(&self) -> usize {
+        self.cells.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.cells.is_empty()
+    }
+
+    /// Smallest and largest corner of every cell inserted so far
+    pub fn bounds(&self) -> Option<((i64, i64), (i64, i64))> {
+        self.bounds
+    }
+
+    /// The set cells in reading order
+    pub fn iter(&self) -> impl Iterator<Item = ((i64, i64), &T)> {
+        let mut cells: Vec<_> = self.cells.iter().map(|(&pos, value)| (pos, value)).collect();
+        cells.sort_by_key(|&((x, y), _)| (y, x));
+        cells.into_iter()
+    }
+
+    /// The orthogonal neighbours of `pos`, in reading order
+    pub fn neighbours4((x, y): (i64, i64)) -> impl Iterator<Item = (i64, i64)> {
+        NEIGHBOURS_4.iter().map(move |&(dx, dy)| (x + dx, y + dy))
+    }
+
+    /// All neighbours of `pos`, including diagonals, in reading order
+    pub fn neighbours8((x, y): (i64, i64)) -> impl Iterator<Item = (i64, i64)> {
+        NEIGHBOURS_8.iter().map(move |&(dx, dy)| (x + dx, y + dy))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MAP: &str = "#.#\n..#\n#..";
+
+    fn wall(c: char) -> Option<bool> {
+        match c {
+            '#' => Some(true),
+            '.' => Some(false),
+            _ => None,
+        }
+    }
+
+    #[test]
+    fn test_parse_and_render() {
+        let grid = Grid::parse(MAP, wall).unwrap();
+        assert_eq!((grid.width(), grid.height()), (3, 3));
+        assert!(grid[(2, 1)]);
+        assert!(!grid[(1, 1)]);
+        assert_eq!(grid.count(|&wall| wall), 4);
+        assert_eq!(grid.render(|&wall| if wall { '#' } else { '.' }), MAP);
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        let err = Grid::parse("#.#\n.x#", wall).unwrap_err();
+        assert!(matches!(err, AocError::Parse { line: 2, column: 2, .. }));
+        let err = Grid::parse("#.#\n.#\n", wall).unwrap_err();
+        assert!(matches!(err, AocError::Parse { line: 2, column: 1, .. }));
+    }
+
+    #[test]
+    fn test_bounds() {
+        let mut grid = Grid::new(3, 2, 0);
+        assert_eq!(grid.get((3, 0)), None);
+        assert_eq!(grid.position(-1, 0), None);
+        assert_eq!(grid.position(2, 1), Some((2, 1)));
+        grid[(2, 1)] = 5;
+        assert_eq!(grid.get((2, 1)), Some(&5));
+        assert_eq!(grid.rows().last(), Some(&[0, 0, 5][..]));
+    }
+
+    #[test]
+    fn test_neighbours_in_reading_order() {
+        let grid = Grid::new(3, 3, ());
+        let around: Vec<_> = grid.neighbours4((1, 1)).collect();
+        assert_eq!(around, vec![(1, 0), (0, 1), (2, 1), (1, 2)]);
+        assert_eq!(grid.neighbours4((0, 0)).collect::<Vec<_>>(), vec![(1, 0), (0, 1)]);
+        assert_eq!(grid.neighbours8((1, 1)).count(), 8);
+        assert_eq!(grid.neighbours8((2, 2)).collect::<Vec<_>>(), vec![(1, 1), (2, 1), (1, 2)]);
+    }
+
+    #[test]
+    fn test_reading_order_and_map() {
+        let grid = Grid::parse(MAP, wall).unwrap();
+        let walls: Vec<_> = grid.iter().filter(|(_, &wall)| wall).map(|(pos, _)| pos).collect();
+        assert_eq!(walls, vec![(0, 0), (2, 0), (2, 1), (0, 2)]);
+
+        let sums = grid.map(|(x, y), _| x + y);
+        assert_eq!(sums[(2, 2)], 4);
+    }
+
+    #[test]
+    fn test_sparse_grid_grows() {
+        let mut grid = SparseGrid::parse(MAP, |c| wall(c).map(|wall| wall.then_some('#'))).unwrap();
+        assert_eq!(grid.len(), 4);
+        assert_eq!(grid.bounds(), Some(((0, 0), (2, 2))));
+
+        grid.insert((-2, 3), '~');
+        assert_eq!(grid.bounds(), Some(((-2, 0), (2, 3))));
+        assert_eq!(
+            grid.render(|cell| cell.copied().unwrap_or('.')),
+            "..#.#\n....#\n..#..\n~...."
+        );
+        assert_eq!(grid.iter().next(), Some(((0, 0), &'#')));
+        assert_eq!(SparseGrid::<char>::neighbours4((0, 0)).next(), Some((0, -1)));
+        assert!(SparseGrid::parse("#?", |c| wall(c).map(|_| Some(()))).is_err());
+    }
+}
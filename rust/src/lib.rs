@@ -1,17 +1,105 @@
+use config::Config;
+use std::collections::HashMap;
 use std::fs;
 use std::path::Path;
 
-/// Read input file for a given day
-pub fn read_input(day: u8) -> String {
-    let filename = format!("inputs/day{:02}.txt", day);
-    fs::read_to_string(&filename)
-        .unwrap_or_else(|_| panic!("Failed to read input file: {}", filename))
+pub mod alloc;
+pub mod answers;
+pub mod args;
+pub mod bench;
+pub mod cache;
+pub mod config;
+pub mod cycle;
+pub mod error;
+pub mod geom;
+pub mod grid;
+pub mod parse;
+pub mod pool;
+pub mod puzzle;
+pub mod report;
+pub mod scaffold;
+pub mod search;
+pub mod solution;
+pub mod submit;
+pub mod trace;
+
+pub use error::AocError;
+pub use solution::{Answer, DynSolution, Solution};
+
+/// The event the solutions in this crate are for
+pub const YEAR: u16 = 2018;
+
+/// A solved day that the `aoc` runner can dispatch to
+pub struct Day {
+    pub number: u8,
+    pub solution: &'static dyn DynSolution,
+}
+
+impl Day {
+    /// Parse the input and run one part (1 or 2) against it
+    pub fn run(&self, part: u8, input: &str) -> Result<Answer, AocError> {
+        let parsed = self.solution.parse(input)?;
+        Ok(self.solution.part(part, parsed.as_ref()))
+    }
+}
+
+macro_rules! day {
+    ($number:expr, $module:ident :: $solution:ident) => {
+        Day {
+            number: $number,
+            solution: &$module::$solution,
+        }
+    };
+}
+
+// Day modules and the `DAYS` registry, generated by build.rs from src/dayNN.rs
+include!(concat!(env!("OUT_DIR"), "/days.rs"));
+
+/// Find a registered day by its number
+pub fn find_day(number: u8) -> Option<&'static Day> {
+    DAYS.iter().find(|day| day.number == number)
+}
+
+/// Parse a day list such as `7`, `1..25` (inclusive) or `1,3,10..12`
+pub fn parse_days(spec: &str) -> Result<Vec<u8>, String> {
+    let day = |s: &str| -> Result<u8, String> {
+        match s.trim().parse() {
+            Ok(day) if (1..=25).contains(&day) => Ok(day),
+            _ => Err(format!("Invalid day {:?}: days are 1 to 25", s)),
+        }
+    };
+
+    let mut days = Vec::new();
+    for item in spec.split(',') {
+        match item.split_once("..") {
+            Some((start, end)) => {
+                let (start, end) = (day(start)?, day(end.trim_start_matches('='))?);
+                if start > end {
+                    return Err(format!("Empty day range {:?}", item));
+                }
+                days.extend(start..=end);
+            }
+            None => days.push(day(item)?),
+        }
+    }
+    days.sort();
+    days.dedup();
+    Ok(days)
+}
+
+/// Read input file for a given day from the configured input directory
+pub fn read_input(day: u8) -> Result<String, AocError> {
+    read_input_in(&Config::load(HashMap::new()).input_dir(), day)
+}
+
+/// Read input file for a given day from `dir`
+pub fn read_input_in(dir: &Path, day: u8) -> Result<String, AocError> {
+    read_input_from_path(dir.join(format!("day{:02}.txt", day)))
 }
 
 /// Read input file from a custom path
-pub fn read_input_from_path<P: AsRef<Path>>(path: P) -> String {
-    fs::read_to_string(&path)
-        .unwrap_or_else(|_| panic!("Failed to read input file: {:?}", path.as_ref()))
+pub fn read_input_from_path<P: AsRef<Path>>(path: P) -> Result<String, AocError> {
+    fs::read_to_string(&path).map_err(|e| AocError::io(path.as_ref(), e))
 }
 
 #[cfg(test)]
@@ -22,4 +110,49 @@ mod tests {
     fn test_placeholder() {
         assert_eq!(2 + 2, 4);
     }
+
+    #[test]
+    fn test_registry_is_sorted_and_unique() {
+        for pair in DAYS.windows(2) {
+            assert!(pair[0].number < pair[1].number);
+        }
+    }
+
+    #[test]
+    fn test_find_day() {
+        assert_eq!(find_day(1).map(|day| day.number), Some(1));
+        assert!(find_day(0).is_none());
+        assert!(find_day(26).is_none());
+    }
+
+    #[test]
+    fn test_run_dispatches_to_part() {
+        let day = find_day(1).unwrap();
+        assert_eq!(day.run(1, "+1\n+1\n-2").unwrap(), Answer::Integer(0));
+        assert_eq!(day.run(2, "+1\n-1").unwrap(), Answer::Integer(0));
+    }
+
+    #[test]
+    fn test_run_reports_parse_errors() {
+        let day = find_day(1).unwrap();
+        let err = day.run(1, "+1\n+x\n").unwrap_err();
+        assert!(matches!(err, AocError::Parse { line: 2, column: 1, .. }));
+    }
+
+    #[test]
+    fn test_parse_days() {
+        assert_eq!(parse_days("7"), Ok(vec![7]));
+        assert_eq!(parse_days("1..25").unwrap().len(), 25);
+        assert_eq!(parse_days("3,10..=12,3"), Ok(vec![3, 10, 11, 12]));
+        assert!(parse_days("0").is_err());
+        assert!(parse_days("20..26").is_err());
+        assert!(parse_days("5..2").is_err());
+        assert!(parse_days("x").is_err());
+    }
+
+    #[test]
+    fn test_read_input_missing_file() {
+        let err = read_input_from_path("inputs/does-not-exist.txt").unwrap_err();
+        assert!(matches!(err, AocError::MissingInput { .. }));
+    }
 }
@@ -0,0 +1,125 @@
+//! Helpers for parsing lines of puzzle input
+//!
+//! Like `parse_at`, they take the whole `input` along with the `line` being
+//! parsed, which must be a slice of it, so that errors point at the line and
+//! column where the line stops matching.
+
+use crate::error::{parse_at, AocError, Result};
+use std::fmt::Display;
+use std::str::FromStr;
+
+/// All integers in `line`, with an optional `-` or `+` sign directly before
+/// the digits
+///
+/// `position=< 9, -1> velocity=< 0,  2>` gives `[9, -1, 0, 2]`.
+pub fn ints<T>(input: &str, line: &str) -> Result<Vec<T>>
+where
+    T: FromStr,
+    T::Err: Display,
+{
+    let bytes = line.as_bytes();
+    let mut values = Vec::new();
+    let mut i = 0;
+    while i < bytes.len() {
+        let signed = matches!(bytes[i], b'-' | b'+') && bytes.get(i + 1).is_some_and(u8::is_ascii_digit);
+        if !signed && !bytes[i].is_ascii_digit() {
+            i += 1;
+            continue;
+        }
+        let start = i;
+        i += 1;
+        while i < bytes.len() && bytes[i].is_ascii_digit() {
+            i += 1;
+        }
+        values.push(parse_at(input, &line[start..i])?);
+    }
+    Ok(values)
+}
+
+/// Exactly `N` integers from `line`, see `ints`
+pub fn exact_ints<T, const N: usize>(input: &str, line: &str) -> Result<[T; N]>
+where
+    T: FromStr,
+    T::Err: Display,
+{
+    ints(input, line)?.try_into().map_err(|values: Vec<T>| {
+        AocError::at(input, line, format!("expected {} numbers, found {}", N, values.len()))
+    })
+}
+
+/// Match `line` against a `pattern` with `N` `{}` placeholders and return what
+/// each placeholder matched, trimmed
+///
+/// A placeholder matches up to the first occurrence of the text after it, the
+/// last one up to the end of the line. Only trailing whitespace may follow the
+/// pattern.
+///
+/// Panics if `pattern` doesn't have `N` placeholders with text between them.
+pub fn captures<'a, const N: usize>(input: &str, line: &'a str, pattern: &str) -> Result<[&'a str; N]> {
+    let literals: Vec<&str> = pattern.split("{}").collect();
+    assert!(
+        literals.len() == N + 1 && literals.iter().skip(1).take(N.saturating_sub(1)).all(|literal| !literal.is_empty()),
+        "pattern {:?} should have {} separated placeholders",
+        pattern,
+        N
+    );
+    let expected = |rest: &'a str, literal: &str| AocError::at(input, rest, format!("expected {:?}", literal));
+
+    let mut rest = line.strip_prefix(literals[0]).ok_or_else(|| expected(line, literals[0]))?;
+    let mut fields = [""; N];
+    for (field, literal) in fields.iter_mut().zip(&literals[1..]) {
+        let end = if literal.is_empty() {
+            rest.len()
+        } else {
+            rest.find(literal).ok_or_else(|| expected(rest, literal))?
+        };
+        *field = rest[..end].trim();
+        rest = &rest[end + literal.len()..];
+    }
+
+    let rest = rest.trim();
+    if !rest.is_empty() {
+        return Err(AocError::at(input, rest, format!("unexpected {:?} at the end of the line", rest)));
+    }
+    Ok(fields)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ints() {
+        let line = "position=< 9, -1> velocity=<+3,  2> x=495..501 - 7";
+        assert_eq!(ints::<i64>(line, line).unwrap(), vec![9, -1, 3, 2, 495, 501, 7]);
+        assert_eq!(exact_ints::<u8, 2>("1,2", "1,2").unwrap(), [1, 2]);
+
+        let input = "1, 2\n3, 4, 5";
+        let err = exact_ints::<i32, 2>(input, input.lines().nth(1).unwrap()).unwrap_err();
+        assert!(matches!(err, AocError::Parse { line: 2, column: 1, .. }));
+        assert!(err.to_string().contains("expected 2 numbers, found 3"));
+
+        let err = ints::<u8>(input, "300 players").unwrap_err();
+        assert!(err.to_string().contains("\"300\""));
+    }
+
+    #[test]
+    fn test_captures() {
+        let line = "pos=<0,-1,2>, r=4";
+        assert_eq!(captures(line, line, "pos=<{},{},{}>, r={}").unwrap(), ["0", "-1", "2", "4"]);
+        assert_eq!(captures(line, line, "{}=<{}>{}").unwrap(), ["pos", "0,-1,2", ", r=4"]);
+
+        let input = "10 players; last marble is worth 1618 points\n9 players, last marble is worth 25 points \n";
+        let pattern = "{} players; last marble is worth {} points";
+        let [players, last] = captures(input, input.lines().next().unwrap(), pattern).unwrap();
+        assert_eq!((players, last), ("10", "1618"));
+        assert!(matches!(parse_at::<u32>(input, last), Ok(1618)));
+
+        let err = captures::<2>(input, input.lines().nth(1).unwrap(), pattern).unwrap_err();
+        assert!(matches!(err, AocError::Parse { line: 2, column: 1, .. }));
+        assert!(err.to_string().contains("expected \" players; last marble is worth \""));
+
+        let err = captures::<1>(input, input.lines().next().unwrap(), "{} players;").unwrap_err();
+        assert!(matches!(err, AocError::Parse { line: 1, column: 13, .. }));
+    }
+}
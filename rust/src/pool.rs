@@ -0,0 +1,92 @@
+//! A small thread pool for running independent days in parallel
+
+use std::collections::BTreeMap;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::mpsc;
+use std::thread;
+
+/// Run `work` on every item on up to `jobs` threads, passing each result to `emit`
+/// on the calling thread
+///
+/// Results are emitted in item order as soon as all earlier items are done, so
+/// with one job this behaves like a plain loop.
+pub fn for_each_ordered<T: Sync, R: Send>(
+    items: &[T],
+    jobs: usize,
+    work: impl Fn(&T) -> R + Sync,
+    mut emit: impl FnMut(&T, R),
+) {
+    let next = AtomicUsize::new(0);
+    let (sender, receiver) = mpsc::channel();
+
+    thread::scope(|scope| {
+        for _ in 0..jobs.clamp(1, items.len().max(1)) {
+            let sender = sender.clone();
+            let (next, work) = (&next, &work);
+            scope.spawn(move || loop {
+                let index = next.fetch_add(1, Ordering::Relaxed);
+                let Some(item) = items.get(index) else {
+                    break;
+                };
+                if sender.send((index, work(item))).is_err() {
+                    break;
+                }
+            });
+        }
+        drop(sender);
+
+        // Hold back results that finish before an earlier item
+        let mut pending = BTreeMap::new();
+        let mut emitted = 0;
+        for (index, result) in receiver {
+            pending.insert(index, result);
+            while let Some(result) = pending.remove(&emitted) {
+                emit(&items[emitted], result);
+                emitted += 1;
+            }
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[test]
+    fn test_results_are_emitted_in_order() {
+        let items: Vec<u64> = (0..20).collect();
+        for jobs in [1, 4, 50] {
+            let mut seen = Vec::new();
+            for_each_ordered(
+                &items,
+                jobs,
+                |&n| {
+                    // Later items finish first
+                    thread::sleep(Duration::from_millis(20 - n));
+                    n * n
+                },
+                |&n, square| seen.push((n, square)),
+            );
+            assert_eq!(seen, items.iter().map(|&n| (n, n * n)).collect::<Vec<_>>());
+        }
+    }
+
+    #[test]
+    fn test_runs_in_parallel() {
+        let running = AtomicUsize::new(0);
+        let most = AtomicUsize::new(0);
+        for_each_ordered(
+            &[(); 4],
+            4,
+            |_| {
+                let now = running.fetch_add(1, Ordering::SeqCst) + 1;
+                most.fetch_max(now, Ordering::SeqCst);
+                thread::sleep(Duration::from_millis(50));
+                running.fetch_sub(1, Ordering::SeqCst);
+            },
+            |_, _| {},
+        );
+        assert!(most.load(Ordering::SeqCst) > 1);
+    }
+}
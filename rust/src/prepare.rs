@@ -1,19 +1,43 @@
+use aoc2018::answers::record_answer;
+use aoc2018::cache::{validate_input, Manifest, Status, Throttle};
+use aoc2018::config::{user_config_path, Config};
+use aoc2018::puzzle::{articles, examples, puzzle_markdown, raw_string_literal, Example};
+use aoc2018::scaffold::{is_discoverable, remove_day_bins, render_template, DEFAULT_DAY_TEMPLATE};
+use aoc2018::submit::{load_log, log_submission, wait_seconds, Outcome, Submission};
+use aoc2018::{find_day, parse_days, read_input_in, YEAR};
 use std::env;
 use std::fs;
-use std::io::Write;
+use std::io::{IsTerminal, Write};
 use std::path::Path;
+use std::time::{SystemTime, UNIX_EPOCH};
 
-fn main() {
-    let args: Vec<String> = env::args().collect();
-    
-    if args.len() != 2 {
-        eprintln!("Usage: cargo run --bin prepare <day>");
-        eprintln!("Example: cargo run --bin prepare 1");
-        std::process::exit(1);
-    }
+fn usage() -> ! {
+    eprintln!("Usage: cargo run --bin prepare [options] <days>");
+    eprintln!("       cargo run --bin prepare [options] submit <day> <part> [answer]");
+    eprintln!("Example: cargo run --bin prepare 1");
+    eprintln!("         cargo run --bin prepare --year 2017 --no-overwrite 1..25");
+    eprintln!();
+    eprintln!("<days> is a day, an inclusive range like 1..25, or a comma separated list of both.");
+    eprintln!("Without an answer, submit runs the registered solution to compute it");
+    eprintln!();
+    eprintln!("  --force         overwrite existing inputs without asking");
+    eprintln!("  --no-overwrite  keep existing inputs without asking");
+    eprintln!("  --dry-run       show what would be downloaded and written, without doing it");
+    eprintln!();
+    eprintln!("Existing inputs are only asked about on a terminal; otherwise they are kept.");
+    eprintln!();
+    eprintln!("Settings (each can also be set in the environment, .env or the user config file):");
+    eprintln!("  --session <cookie>    AOC_SESSION");
+    eprintln!("  --year <year>         AOC_YEAR (default 2018)");
+    eprintln!("  --user-agent <agent>  AOC_USER_AGENT");
+    eprintln!("  --input-dir <dir>     AOC_INPUT_DIR (default inputs)");
+    eprintln!("  --base-url <url>      AOC_BASE_URL (default https://adventofcode.com)");
+    std::process::exit(1);
+}
 
-    let day: u8 = args[1].parse().unwrap_or_else(|_| {
-        eprintln!("Invalid day number: {}", args[1]);
+fn parse_day(arg: &str) -> u8 {
+    let day: u8 = arg.parse().unwrap_or_else(|_| {
+        eprintln!("Invalid day number: {}", arg);
         std::process::exit(1);
     });
 
@@ -21,122 +45,506 @@ fn main() {
         eprintln!("Day must be between 1 and 25");
         std::process::exit(1);
     }
+    day
+}
 
-    // Get session cookie from environment variable
-    let session = env::var("AOC_SESSION").unwrap_or_else(|_| {
-        eprintln!("Error: AOC_SESSION environment variable not set");
-        eprintln!();
-        eprintln!("To set your session cookie:");
-        eprintln!("1. Log in to https://adventofcode.com");
-        eprintln!("2. Open browser DevTools (F12)");
-        eprintln!("3. Go to Application/Storage > Cookies");
-        eprintln!("4. Copy the value of the 'session' cookie");
-        eprintln!("5. Set it as an environment variable:");
-        eprintln!("   PowerShell: $env:AOC_SESSION=\"your_session_cookie\"");
-        eprintln!("   Or create a .env file with: AOC_SESSION=your_session_cookie");
-        std::process::exit(1);
-    });
+/// What to do with an input file that already exists
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Overwrite {
+    /// Ask on a terminal, keep the file otherwise
+    Ask,
+    Always,
+    Never,
+}
 
-    let url = format!("https://adventofcode.com/2018/day/{}/input", day);
-    let output_file = format!("inputs/day{:02}.txt", day);
+struct Options {
+    overwrite: Overwrite,
+    dry_run: bool,
+}
 
-    println!("Downloading input for day {}...", day);
+/// Split `--force`, `--no-overwrite` and `--dry-run` out of `args`
+fn parse_options(args: Vec<String>) -> (Options, Vec<String>) {
+    let mut options = Options {
+        overwrite: Overwrite::Ask,
+        dry_run: false,
+    };
+    let mut rest = Vec::new();
 
-    // Create inputs directory if it doesn't exist
-    fs::create_dir_all("inputs").expect("Failed to create inputs directory");
-
-    // Check if file already exists
-    if Path::new(&output_file).exists() {
-        print!("File {} already exists. Overwrite? (y/N): ", output_file);
-        std::io::stdout().flush().unwrap();
-        
-        let mut response = String::new();
-        std::io::stdin().read_line(&mut response).unwrap();
-        
-        if !response.trim().eq_ignore_ascii_case("y") {
-            println!("Aborted.");
-            return;
+    for arg in args {
+        let overwrite = match arg.as_str() {
+            "--force" => Overwrite::Always,
+            "--no-overwrite" => Overwrite::Never,
+            "--dry-run" => {
+                options.dry_run = true;
+                continue;
+            }
+            _ => {
+                rest.push(arg);
+                continue;
+            }
+        };
+        if options.overwrite != Overwrite::Ask && options.overwrite != overwrite {
+            eprintln!("--force and --no-overwrite cannot be combined");
+            usage();
         }
+        options.overwrite = overwrite;
     }
+    (options, rest)
+}
 
-    // Download the input
-    let response = ureq::get(&url)
-        .set("Cookie", &format!("session={}", session))
-        .set("User-Agent", "github.com/aoc2018 by muzta")
-        .call();
+/// Settings for talking to the Advent of Code site
+struct Client {
+    base_url: String,
+    year: u16,
+    session: String,
+    user_agent: String,
+    throttle: Throttle,
+}
 
+impl Client {
+    /// Client from the settings; a dry run does not need a session cookie
+    fn new(config: &Config, dry_run: bool) -> Self {
+        let session = config.session().unwrap_or_else(|e| {
+            if dry_run {
+                println!("Note: {}", e);
+                return String::new();
+            }
+            eprintln!("Error: {}", e);
+            eprintln!();
+            eprintln!("To set your session cookie:");
+            eprintln!("1. Log in to https://adventofcode.com");
+            eprintln!("2. Open browser DevTools (F12)");
+            eprintln!("3. Go to Application/Storage > Cookies");
+            eprintln!("4. Copy the value of the 'session' cookie");
+            eprintln!("5. Pass it with --session, set the AOC_SESSION environment variable,");
+            eprintln!("   or add AOC_SESSION=your_session_cookie to .env or the user config file");
+            if let Some(path) = user_config_path() {
+                eprintln!("   ({})", path.display());
+            }
+            std::process::exit(1);
+        });
+        let year = config.year().unwrap_or_else(|e| {
+            eprintln!("Error: {}", e);
+            std::process::exit(1);
+        });
+        let interval = config.request_interval().unwrap_or_else(|e| {
+            eprintln!("Error: {}", e);
+            std::process::exit(1);
+        });
+
+        Client {
+            base_url: config.base_url(),
+            year,
+            session,
+            user_agent: config.user_agent(),
+            throttle: Throttle::new(config.input_dir().join(".last-request"), interval),
+        }
+    }
+
+    /// URL of a page of the configured year, e.g. `day/7/input`
+    fn url(&self, path: &str) -> String {
+        format!("{}/{}/{}", self.base_url, self.year, path)
+    }
+
+    /// A request with the session cookie, sent no sooner than the request interval allows
+    fn request(&self, method: &str, url: &str) -> ureq::Request {
+        self.throttle.wait();
+        ureq::request(method, url)
+            .set("Cookie", &format!("session={}", self.session))
+            .set("User-Agent", &self.user_agent)
+    }
+
+    /// GET a page from the Advent of Code site with the session cookie
+    fn fetch(&self, url: &str) -> Result<String, String> {
+        read_response(self.request("GET", url).call())
+    }
+}
+
+fn read_response(response: Result<ureq::Response, ureq::Error>) -> Result<String, String> {
     match response {
-        Ok(resp) => {
-            let content = resp.into_string().unwrap_or_else(|e| {
-                eprintln!("Failed to read response: {}", e);
-                std::process::exit(1);
-            });
-
-            fs::write(&output_file, content).unwrap_or_else(|e| {
-                eprintln!("Failed to write to file: {}", e);
-                std::process::exit(1);
-            });
-
-            println!("✓ Successfully saved input to {}", output_file);
+        Ok(resp) => resp
+            .into_string()
+            .map_err(|e| format!("Failed to read response: {}", e)),
+        Err(ureq::Error::Status(code, _)) => Err(format!("HTTP {}", code)),
+        Err(e) => Err(format!("Request failed: {}", e)),
+    }
+}
+
+/// Whether an existing input file should be replaced
+fn should_overwrite(path: &Path, options: &Options) -> bool {
+    match options.overwrite {
+        Overwrite::Always => true,
+        Overwrite::Never => false,
+        Overwrite::Ask if options.dry_run || !std::io::stdin().is_terminal() => false,
+        Overwrite::Ask => {
+            print!("File {} already exists. Overwrite? (y/N): ", path.display());
+            std::io::stdout().flush().unwrap();
+
+            let mut response = String::new();
+            std::io::stdin().read_line(&mut response).unwrap();
+            response.trim().eq_ignore_ascii_case("y")
         }
-        Err(ureq::Error::Status(code, _)) => {
-            eprintln!("Failed to download input: HTTP {}", code);
-            eprintln!("Make sure your AOC_SESSION cookie is valid and day {} is unlocked", day);
-            std::process::exit(1);
+    }
+}
+
+/// Download the input, handling an existing file as the options say
+///
+/// An input that still matches the cache manifest is not downloaded again
+/// unless `--force` is given.
+fn download_input(client: &Client, day: u8, input_dir: &Path, options: &Options) {
+    let url = client.url(&format!("day/{}/input", day));
+    let file_name = format!("day{:02}.txt", day);
+    let output_file = input_dir.join(&file_name);
+    // Inputs of other years go to the same file names, so the manifest keys include the year
+    let key = format!("{}/{}", client.year, file_name);
+    let mut manifest = Manifest::load(input_dir).unwrap_or_else(|e| {
+        eprintln!("Error: {}", e);
+        std::process::exit(1);
+    });
+
+    if let Ok(existing) = fs::read(&output_file) {
+        match manifest.check(&key, &existing) {
+            Status::Valid if options.overwrite != Overwrite::Always => {
+                println!("✓ Input {} is cached and intact", output_file.display());
+                return;
+            }
+            Status::Changed { expected } => println!(
+                "Warning: {} has changed since it was downloaded ({} bytes, expected {}); it may be truncated or edited",
+                output_file.display(),
+                existing.len(),
+                expected.size
+            ),
+            _ => {}
         }
-        Err(e) => {
-            eprintln!("Request failed: {}", e);
-            std::process::exit(1);
+        if !should_overwrite(&output_file, options) {
+            println!("✓ Keeping existing input {} (use --force to replace it)", output_file.display());
+            return;
         }
     }
+    if options.dry_run {
+        println!("Would download {} to {}", url, output_file.display());
+        return;
+    }
 
-    // Create template day file if it doesn't exist
-    let day_file = format!("src/day{:02}.rs", day);
-    if !Path::new(&day_file).exists() {
-        let template = format!(r#"use aoc2018::read_input;
+    println!("Downloading input for day {}...", day);
+
+    // Create inputs directory if it doesn't exist
+    fs::create_dir_all(input_dir).expect("Failed to create inputs directory");
+
+    let content = client.fetch(&url).unwrap_or_else(|e| {
+        eprintln!("Failed to download input: {}", e);
+        eprintln!("Make sure your AOC_SESSION cookie is valid and day {} of {} is unlocked", day, client.year);
+        std::process::exit(1);
+    });
+    if let Err(e) = validate_input(&content) {
+        eprintln!("Refusing to save the input for day {}: {}", day, e);
+        std::process::exit(1);
+    }
 
-fn part1(input: &str) -> i32 {{
-    // TODO: Implement part 1
-    0
-}}
+    fs::write(&output_file, &content).unwrap_or_else(|e| {
+        eprintln!("Failed to write to file: {}", e);
+        std::process::exit(1);
+    });
+    manifest.record(&key, content.as_bytes()).unwrap_or_else(|e| {
+        eprintln!("Error: {}", e);
+        std::process::exit(1);
+    });
 
-fn part2(input: &str) -> i32 {{
-    // TODO: Implement part 2
-    0
-}}
+    println!("✓ Successfully saved input to {}", output_file.display());
+}
 
-fn main() {{
-    let input = read_input({});
+/// Save the puzzle description as Markdown, refreshing it so part 2 appears once unlocked
+///
+/// Returns the puzzle page so the examples can be turned into tests.
+fn download_puzzle(client: &Client, day: u8, options: &Options) -> Option<String> {
+    let url = client.url(&format!("day/{}", day));
+    let output_file = format!("puzzles/day{:02}.md", day);
 
-    println!("Part 1: {{}}", part1(&input));
-    println!("Part 2: {{}}", part2(&input));
-}}
+    if options.dry_run {
+        println!("Would download {} to {}", url, output_file);
+        return None;
+    }
 
-#[cfg(test)]
-mod tests {{
-    use super::*;
+    println!("Downloading puzzle description for day {}...", day);
+
+    let page = match client.fetch(&url) {
+        Ok(page) => page,
+        Err(e) => {
+            eprintln!("Failed to download puzzle description: {}", e);
+            return None;
+        }
+    };
+    let Some(markdown) = puzzle_markdown(&page) else {
+        eprintln!("No puzzle description found on {}", url);
+        return None;
+    };
+
+    fs::create_dir_all("puzzles").expect("Failed to create puzzles directory");
+    fs::write(&output_file, markdown).unwrap_or_else(|e| {
+        eprintln!("Failed to write to file: {}", e);
+        std::process::exit(1);
+    });
+
+    println!("✓ Successfully saved puzzle description to {}", output_file);
+    Some(page)
+}
 
-    #[test]
-    fn test_part1() {{
+/// Test functions for the template, one per example with a known answer
+///
+/// A part without examples gets a placeholder test to fill in by hand.
+fn example_tests(day: u8, parts: &[Vec<Example>]) -> String {
+    let mut tests = Vec::new();
+    for part in 1..=2 {
+        let examples = parts.get(part - 1).map_or(&[][..], Vec::as_slice);
+        if examples.is_empty() {
+            tests.push(format!(
+                r#"    #[test]
+    fn test_part{part}() {{
         let input = "";
-        assert_eq!(part1(input), 0);
+        assert_eq!(part{part}(&Day{day:02}.parse(input).unwrap()), 0);
     }}
+"#,
+                part = part,
+                day = day
+            ));
+        }
 
-    #[test]
-    fn test_part2() {{
-        let input = "";
-        assert_eq!(part2(input), 0);
+        for (i, example) in examples.iter().enumerate() {
+            let name = if examples.len() == 1 {
+                format!("test_part{}", part)
+            } else {
+                format!("test_part{}_example{}", part, i + 1)
+            };
+            tests.push(format!(
+                r#"    #[test]
+    fn {name}() {{
+        let input = {input};
+        assert_eq!(part{part}(&Day{day:02}.parse(input).unwrap()).to_string(), {answer:?});
     }}
-}}
-"#, day);
+"#,
+                name = name,
+                input = raw_string_literal(&example.input),
+                part = part,
+                day = day,
+                answer = example.answer
+            ));
+        }
+    }
+    tests.join("\n").trim_end().to_string()
+}
 
-        fs::write(&day_file, template).unwrap_or_else(|e| {
-            eprintln!("Failed to write template file: {}", e);
+/// Compute the answer with the registered solution for the day
+fn solve(config: &Config, year: u16, day: u8, part: u8) -> String {
+    if year != YEAR {
+        eprintln!("The registered solutions are for {}, not {}; pass the answer explicitly", YEAR, year);
+        std::process::exit(1);
+    }
+    let Some(solution) = find_day(day) else {
+        eprintln!("Day {} has no registered solution; pass the answer explicitly", day);
+        std::process::exit(1);
+    };
+    let answer = read_input_in(&config.input_dir(), day)
+        .and_then(|input| solution.run(part, &input))
+        .unwrap_or_else(|e| {
+            eprintln!("Error: {}", e);
             std::process::exit(1);
         });
 
-        println!("✓ Created template file {}", day_file);
-    } else {
+    if answer.is_multiline() {
+        eprintln!("The answer has to be read from its output; pass it explicitly:");
+        eprintln!("{}", answer);
+        std::process::exit(1);
+    }
+    answer.to_string()
+}
+
+/// Submit an answer unless the log shows it is wrong, and record the outcome
+fn submit(config: &Config, args: &[String]) {
+    if args.len() < 2 || args.len() > 3 {
+        usage();
+    }
+    let day = parse_day(&args[0]);
+    let part: u8 = match args[1].as_str() {
+        "1" => 1,
+        "2" => 2,
+        _ => {
+            eprintln!("Part must be 1 or 2");
+            std::process::exit(1);
+        }
+    };
+    let year = config.year().unwrap_or_else(|e| {
+        eprintln!("Error: {}", e);
+        std::process::exit(1);
+    });
+    let answer = match args.get(2) {
+        Some(answer) => answer.trim().to_string(),
+        None => solve(config, year, day, part),
+    };
+
+    let log = load_log(year, day).unwrap_or_else(|e| {
+        eprintln!("Error: {}", e);
+        std::process::exit(1);
+    });
+    let now = || SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |d| d.as_secs());
+    if let Some(reason) = log.rejection(part, &answer, now()) {
+        eprintln!("Not submitting: {}", reason);
+        std::process::exit(1);
+    }
+
+    println!("Submitting {} for day {} part {}...", answer, day, part);
+    let client = Client::new(config, false);
+    let url = client.url(&format!("day/{}/answer", day));
+    let level = part.to_string();
+    let response = client
+        .request("POST", &url)
+        .send_form(&[("level", level.as_str()), ("answer", answer.as_str())]);
+    let response = read_response(response).unwrap_or_else(|e| {
+        eprintln!("Failed to submit answer: {}", e);
+        std::process::exit(1);
+    });
+
+    let outcome = Outcome::parse(&response);
+    println!("{}", outcome);
+
+    let timestamp = now();
+    let submission = Submission {
+        timestamp,
+        part,
+        answer,
+        outcome,
+        wait_until: match outcome {
+            Outcome::Wait { seconds } => Some(timestamp + seconds),
+            _ => wait_seconds(&response).map(|seconds| timestamp + seconds),
+        },
+    };
+    let mut logged = log_submission(year, day, &submission);
+    if outcome == Outcome::Correct {
+        logged = logged.and_then(|()| record_answer(year, day, part, &submission.answer));
+    }
+    if let Err(e) = logged {
+        eprintln!("Error: {}", e);
+        std::process::exit(1);
+    }
+
+    if outcome != Outcome::Correct {
+        std::process::exit(1);
+    }
+}
+
+/// Write the `src/dayNN.rs` template with tests from the puzzle examples, unless it exists
+fn create_template(day: u8, year: u16, parts: &[Vec<Example>], options: &Options) {
+    let day_file = format!("src/day{:02}.rs", day);
+    if Path::new(&day_file).exists() {
         println!("✓ Template file {} already exists, skipping", day_file);
+        return;
+    }
+    if options.dry_run {
+        println!("Would create template file {}", day_file);
+        return;
+    }
+
+    // A templates/day.rs in the current directory replaces the built-in template
+    let template = match fs::read_to_string("templates/day.rs") {
+        Ok(template) => template,
+        Err(_) => DEFAULT_DAY_TEMPLATE.to_string(),
+    };
+    let (day_number, day_padded) = (day.to_string(), format!("{:02}", day));
+    let (year, tests) = (year.to_string(), example_tests(day, parts));
+    let values = [
+        ("day", day_number.as_str()),
+        ("day_padded", day_padded.as_str()),
+        ("year", year.as_str()),
+        ("examples", tests.as_str()),
+    ];
+    let template = render_template(&template, &values).unwrap_or_else(|e| {
+        eprintln!("Failed to render templates/day.rs: {}", e);
+        std::process::exit(1);
+    });
+
+    fs::write(&day_file, template).unwrap_or_else(|e| {
+        eprintln!("Failed to write template file: {}", e);
+        std::process::exit(1);
+    });
+
+    println!("✓ Created template file {}", day_file);
+    let found: usize = parts.iter().map(Vec::len).sum();
+    println!("  Generated {} test(s) from the puzzle examples", found);
+}
+
+/// Make sure the build picks up `src/dayNN.rs`, reporting what had to change
+///
+/// build.rs registers the day, so this only removes leftover per-day `[[bin]]`
+/// targets from `Cargo.toml`. Running it again changes nothing.
+fn register_day(day: u8, options: &Options) {
+    let day_file = format!("src/day{:02}.rs", day);
+    if !Path::new("build.rs").exists() {
+        println!("Note: there is no build.rs here, so {} has to be registered by hand", day_file);
+        return;
+    }
+    if let Ok(source) = fs::read_to_string(&day_file) {
+        if !is_discoverable(&source, day) {
+            println!(
+                "Warning: {} has no `pub struct Day{:02}`, so build.rs will not register it",
+                day_file, day
+            );
+        }
+    }
+
+    let Ok(cargo_toml) = fs::read_to_string("Cargo.toml") else {
+        return;
+    };
+    let (updated, removed) = remove_day_bins(&cargo_toml, day);
+    if removed.is_empty() {
+        println!("✓ Day {} is registered by build.rs, Cargo.toml needs no changes", day);
+    } else if options.dry_run {
+        println!("Would remove [[bin]] {} from Cargo.toml", removed.join(", "));
+    } else {
+        fs::write("Cargo.toml", updated).unwrap_or_else(|e| {
+            eprintln!("Failed to update Cargo.toml: {}", e);
+            std::process::exit(1);
+        });
+        println!(
+            "✓ Removed [[bin]] {} from Cargo.toml; {} is registered by build.rs instead",
+            removed.join(", "),
+            day_file
+        );
+    }
+}
+
+fn main() {
+    let args: Vec<String> = env::args().skip(1).collect();
+    let (flags, args) = Config::split_flags(&args).unwrap_or_else(|e| {
+        eprintln!("{}", e);
+        usage();
+    });
+    let config = Config::load(flags);
+
+    if args.first().map(String::as_str) == Some("submit") {
+        return submit(&config, &args[1..]);
+    }
+    let (options, args) = parse_options(args);
+    if args.len() != 1 {
+        usage();
+    }
+
+    let days = parse_days(&args[0]).unwrap_or_else(|e| {
+        eprintln!("{}", e);
+        std::process::exit(1);
+    });
+    let client = Client::new(&config, options.dry_run);
+    let input_dir = config.input_dir();
+
+    for day in days {
+        if options.dry_run {
+            println!("Day {} of {}:", day, client.year);
+        }
+        download_input(&client, day, &input_dir, &options);
+        let page = download_puzzle(&client, day, &options);
+        let parts: Vec<Vec<Example>> = page
+            .as_deref()
+            .map(|page| articles(page).into_iter().map(examples).collect())
+            .unwrap_or_default();
+        create_template(day, client.year, &parts, &options);
+        register_day(day, &options);
     }
 }
@@ -0,0 +1,347 @@
+//! Conversion of Advent of Code puzzle pages to Markdown
+
+/// Piece of an HTML document: a tag or the text between tags
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Token<'a> {
+    Open { name: String, attrs: &'a str },
+    Close { name: String },
+    Text(&'a str),
+}
+
+fn tokenize(html: &str) -> Vec<Token<'_>> {
+    let mut tokens = Vec::new();
+    let mut rest = html;
+
+    while !rest.is_empty() {
+        let Some(start) = rest.find('<') else {
+            tokens.push(Token::Text(rest));
+            break;
+        };
+        if start > 0 {
+            tokens.push(Token::Text(&rest[..start]));
+        }
+        let Some(end) = rest[start..].find('>').map(|i| start + i) else {
+            tokens.push(Token::Text(&rest[start..]));
+            break;
+        };
+
+        let tag = rest[start + 1..end].trim_end_matches('/');
+        if let Some(name) = tag.strip_prefix('/') {
+            tokens.push(Token::Close {
+                name: name.trim().to_ascii_lowercase(),
+            });
+        } else if !tag.starts_with('!') {
+            let (name, attrs) = tag.split_once(char::is_whitespace).unwrap_or((tag, ""));
+            tokens.push(Token::Open {
+                name: name.to_ascii_lowercase(),
+                attrs,
+            });
+        }
+        rest = &rest[end + 1..];
+    }
+    tokens
+}
+
+/// Value of attribute `name` in the attribute text of a tag
+fn attr<'a>(attrs: &'a str, name: &str) -> Option<&'a str> {
+    let start = attrs.find(&format!("{}=\"", name))? + name.len() + 2;
+    let len = attrs[start..].find('"')?;
+    Some(&attrs[start..start + len])
+}
+
+/// Decode the HTML entities Advent of Code pages use
+pub fn decode_entities(text: &str) -> String {
+    let mut decoded = String::with_capacity(text.len());
+    let mut rest = text;
+
+    while let Some(start) = rest.find('&') {
+        decoded.push_str(&rest[..start]);
+        rest = &rest[start..];
+        let entity = rest.find(';').filter(|&end| end <= 10).map(|end| &rest[1..end]);
+        let ch = entity.and_then(|entity| match entity {
+            "lt" => Some('<'),
+            "gt" => Some('>'),
+            "amp" => Some('&'),
+            "quot" => Some('"'),
+            "apos" => Some('\''),
+            "nbsp" => Some(' '),
+            _ => entity
+                .strip_prefix("#x")
+                .and_then(|hex| u32::from_str_radix(hex, 16).ok())
+                .or_else(|| entity.strip_prefix('#').and_then(|dec| dec.parse().ok()))
+                .and_then(char::from_u32),
+        });
+        match (entity, ch) {
+            (Some(entity), Some(ch)) => {
+                decoded.push(ch);
+                rest = &rest[entity.len() + 2..];
+            }
+            _ => {
+                decoded.push('&');
+                rest = &rest[1..];
+            }
+        }
+    }
+    decoded.push_str(rest);
+    decoded
+}
+
+/// The `<article class="day-desc">` sections of a puzzle page, in order
+///
+/// Part 2 only appears once part 1 is solved, so there are one or two.
+pub fn articles(page: &str) -> Vec<&str> {
+    let mut found = Vec::new();
+    let mut rest = page;
+
+    while let Some(start) = rest.find("<article class=\"day-desc\">") {
+        let body = &rest[start + "<article class=\"day-desc\">".len()..];
+        let end = body.find("</article>").unwrap_or(body.len());
+        found.push(&body[..end]);
+        rest = &body[end..];
+    }
+    found
+}
+
+/// Convert the subset of HTML used in puzzle descriptions to Markdown
+pub fn to_markdown(html: &str) -> String {
+    let mut out = String::new();
+    let mut in_pre = false;
+    // Inline code is buffered so an emphasized answer like `<code><em>58</em></code>`
+    // can become *`58`* rather than breaking the code span
+    let mut code: Option<(String, bool)> = None;
+    let mut links: Vec<Option<&str>> = Vec::new();
+
+    for token in tokenize(html) {
+        match token {
+            Token::Text(text) => {
+                let text = decode_entities(text);
+                if in_pre {
+                    out.push_str(&text);
+                } else if let Some((buffer, _)) = code.as_mut() {
+                    buffer.push_str(&text);
+                } else if !(text.trim().is_empty() && text.contains('\n')) {
+                    out.push_str(&text.replace('\n', " "));
+                }
+            }
+            Token::Open { name, attrs } => match name.as_str() {
+                "h2" => out.push_str("## "),
+                "pre" => {
+                    in_pre = true;
+                    out.push_str("```\n");
+                }
+                "code" if !in_pre => code = Some((String::new(), false)),
+                "em" if !in_pre => match code.as_mut() {
+                    Some((buffer, emphasized)) if buffer.is_empty() => *emphasized = true,
+                    Some(_) => {}
+                    None => out.push('*'),
+                },
+                "li" => out.push_str("- "),
+                "a" => {
+                    let href = attr(attrs, "href");
+                    if href.is_some() {
+                        out.push('[');
+                    }
+                    links.push(href);
+                }
+                _ => {}
+            },
+            Token::Close { name } => match name.as_str() {
+                "h2" | "p" | "ul" => out.push_str("\n\n"),
+                "li" => out.push('\n'),
+                "pre" => {
+                    in_pre = false;
+                    if !out.ends_with('\n') {
+                        out.push('\n');
+                    }
+                    out.push_str("```\n\n");
+                }
+                "code" => {
+                    if let Some((buffer, emphasized)) = code.take() {
+                        let marker = if emphasized { "*" } else { "" };
+                        out.push_str(&format!("{}`{}`{}", marker, buffer, marker));
+                    }
+                }
+                "em" if !in_pre && code.is_none() => out.push('*'),
+                "a" => {
+                    if let Some(Some(href)) = links.pop() {
+                        out.push_str(&format!("]({})", href));
+                    }
+                }
+                _ => {}
+            },
+        }
+    }
+
+    let mut markdown = String::new();
+    for line in out.lines() {
+        let line = line.trim_end();
+        if line.is_empty() && (markdown.is_empty() || markdown.ends_with("\n\n")) {
+            continue;
+        }
+        markdown.push_str(line);
+        markdown.push('\n');
+    }
+    markdown.trim_end().to_string() + "\n"
+}
+
+/// An example input from a puzzle description with the answer given for it
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Example {
+    pub input: String,
+    pub answer: String,
+}
+
+/// Examples in one description article, each `<pre><code>` block paired with
+/// the first emphasized answer (`<code><em>..</em></code>`) after it
+///
+/// Code blocks between an example and its answer are usually intermediate
+/// states and are skipped; an example without an answer is dropped.
+pub fn examples(article: &str) -> Vec<Example> {
+    let mut found = Vec::new();
+    let mut pending: Option<String> = None;
+    let mut pre: Option<String> = None;
+    let mut code: Option<(String, bool)> = None;
+    let mut in_em = false;
+
+    for token in tokenize(article) {
+        match token {
+            Token::Text(text) => {
+                if let Some(buffer) = pre.as_mut().or(code.as_mut().map(|(buffer, _)| buffer)) {
+                    buffer.push_str(&decode_entities(text));
+                }
+            }
+            Token::Open { name, .. } => match name.as_str() {
+                "pre" => pre = Some(String::new()),
+                "code" if pre.is_none() => code = Some((String::new(), in_em)),
+                "em" => match code.as_mut() {
+                    Some((buffer, emphasized)) if buffer.is_empty() => *emphasized = true,
+                    _ => in_em = true,
+                },
+                _ => {}
+            },
+            Token::Close { name } => match name.as_str() {
+                "pre" => {
+                    if let Some(block) = pre.take() {
+                        pending.get_or_insert(block);
+                    }
+                }
+                "code" => {
+                    if let Some((answer, true)) = code.take() {
+                        if let Some(input) = pending.take() {
+                            found.push(Example { input, answer });
+                        }
+                    }
+                }
+                "em" => in_em = false,
+                _ => {}
+            },
+        }
+    }
+    found
+}
+
+/// Rust raw string literal for `text`, with enough `#`s to hold any quotes in it
+pub fn raw_string_literal(text: &str) -> String {
+    let hashes = (0..)
+        .map(|n| "#".repeat(n))
+        .find(|hashes| !text.contains(&format!("\"{}", hashes)))
+        .unwrap();
+    format!("r{}\"{}\"{}", hashes, text, hashes)
+}
+
+/// Markdown for all description articles of a puzzle page, or `None` if there are none
+pub fn puzzle_markdown(page: &str) -> Option<String> {
+    let sections: Vec<String> = articles(page).into_iter().map(to_markdown).collect();
+    (!sections.is_empty()).then(|| sections.join("\n"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PAGE: &str = r#"<main>
+<article class="day-desc"><h2>--- Day 1: Chronal Calibration ---</h2><p>The device shows a <em>sequence of changes</em> (your <a href="1/input" target="_blank">puzzle input</a>).</p>
+<ul>
+<li><code>+1, +1, +1</code> results in&nbsp; <code><em>3</em></code></li>
+<li><code>-1, -2</code> results in <code>-3</code></li>
+</ul>
+<pre><code>x &lt; y
+<em>#</em>..
+</code></pre>
+</article>
+<p>Your puzzle answer was <code>439</code>.</p><article class="day-desc"><h2 id="part2">--- Part Two ---</h2><p>What is the <span title="hm">first</span> frequency?</p>
+</article>
+</main>"#;
+
+    #[test]
+    fn test_articles() {
+        let found = articles(PAGE);
+        assert_eq!(found.len(), 2);
+        assert!(found[1].starts_with("<h2 id=\"part2\">"));
+        assert!(articles("<p>Not logged in</p>").is_empty());
+    }
+
+    #[test]
+    fn test_decode_entities() {
+        assert_eq!(decode_entities("a &lt;b&gt; &amp; &#39;c&#x27; & d"), "a <b> & 'c' & d");
+    }
+
+    #[test]
+    fn test_to_markdown() {
+        let markdown = puzzle_markdown(PAGE).unwrap();
+        let expected = "\
+## --- Day 1: Chronal Calibration ---
+
+The device shows a *sequence of changes* (your [puzzle input](1/input)).
+
+- `+1, +1, +1` results in  *`3`*
+- `-1, -2` results in `-3`
+
+```
+x < y
+#..
+```
+
+## --- Part Two ---
+
+What is the first frequency?
+";
+        assert_eq!(markdown, expected);
+    }
+
+    #[test]
+    fn test_examples() {
+        let article = "\
+<p>For example:</p>
+<pre><code>+1
+-2
+</code></pre>
+<p>After that:</p>
+<pre><code>intermediate state</code></pre>
+<p>The result is <code><em>-1</em></code>, not <code><em>0</em></code>.</p>
+<pre><code>a &lt; b</code></pre>
+<p>Here it is <em><code>7</code></em>.</p>
+<pre><code>no answer</code></pre>";
+        let found = examples(article);
+        assert_eq!(
+            found,
+            vec![
+                Example {
+                    input: "+1\n-2\n".to_string(),
+                    answer: "-1".to_string()
+                },
+                Example {
+                    input: "a < b".to_string(),
+                    answer: "7".to_string()
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_raw_string_literal() {
+        assert_eq!(raw_string_literal("#.#\n"), "r\"#.#\n\"");
+        assert_eq!(raw_string_literal("say \"hi\""), "r#\"say \"hi\"\"#");
+        assert_eq!(raw_string_literal("\"#"), "r##\"\"#\"##");
+    }
+}
@@ -0,0 +1,365 @@
+//! Output of `aoc run`: the plain text lines, or one JSON or TSV record per part
+//! for scripts
+
+use crate::alloc::{format_bytes, AllocStats};
+use crate::answers::Verdict;
+use crate::bench::format_duration;
+use crate::Answer;
+use std::collections::BTreeMap;
+use std::time::Duration;
+
+/// How `aoc run` prints its results
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    Text,
+    /// One JSON object per line
+    Json,
+    /// Tab separated values with a header line
+    Tsv,
+}
+
+impl Format {
+    pub fn parse(name: &str) -> Result<Self, String> {
+        match name {
+            "text" => Ok(Format::Text),
+            "json" => Ok(Format::Json),
+            "tsv" => Ok(Format::Tsv),
+            _ => Err(format!("Unknown format {:?}: use text, json or tsv", name)),
+        }
+    }
+}
+
+pub const TSV_HEADER: &str = "day\tpart\tanswer\telapsed_ns\tverdict\texpected\tallocs\talloc_bytes\tpeak_bytes";
+
+/// The result of running one part of a day
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Record {
+    pub day: u8,
+    pub part: u8,
+    pub answer: Answer,
+    pub elapsed: Duration,
+    /// Set when the answer was verified
+    pub verdict: Option<Verdict>,
+    /// Set when allocations were counted
+    pub alloc: Option<AllocStats>,
+}
+
+impl Record {
+    pub fn format(&self, format: Format) -> String {
+        match format {
+            Format::Text => self.to_text(),
+            Format::Json => self.to_json(),
+            Format::Tsv => self.to_tsv(),
+        }
+    }
+
+    /// `Part 1: answer [PASS]`, with a multi-line answer starting on its own line
+    pub fn to_text(&self) -> String {
+        let mut verdict = self.verdict.as_ref().map(|v| format!(" [{}]", v)).unwrap_or_default();
+        if let Some(alloc) = &self.alloc {
+            verdict.push_str(&format!(" ({})", alloc));
+        }
+        if self.answer.is_multiline() {
+            format!("Part {}:{}\n{}", self.part, verdict, self.answer)
+        } else {
+            format!("Part {}: {}{}", self.part, self.answer, verdict)
+        }
+    }
+
+    /// A JSON object; integer answers are numbers, others strings with the rows
+    /// of a multi-line answer joined by `\n`
+    pub fn to_json(&self) -> String {
+        let answer = match &self.answer {
+            Answer::Integer(n) => n.to_string(),
+            answer => json_string(&answer.to_string()),
+        };
+        let mut json = format!(
+            r#"{{"day":{},"part":{},"answer":{},"elapsed_ns":{}"#,
+            self.day,
+            self.part,
+            answer,
+            self.elapsed.as_nanos()
+        );
+        if let Some(verdict) = &self.verdict {
+            json.push_str(&format!(r#","verdict":"{}""#, verdict_name(verdict)));
+            if let Verdict::Fail { expected } = verdict {
+                json.push_str(&format!(r#","expected":{}"#, json_string(expected)));
+            }
+        }
+        if let Some(alloc) = &self.alloc {
+            json.push_str(&format!(
+                r#","allocs":{},"alloc_bytes":{},"peak_bytes":{}"#,
+                alloc.allocs, alloc.bytes, alloc.peak
+            ));
+        }
+        json.push('}');
+        json
+    }
+
+    /// A TSV line in `TSV_HEADER` order, with `tsv_escape`d text fields
+    pub fn to_tsv(&self) -> String {
+        let (verdict, expected) = match &self.verdict {
+            None => ("", ""),
+            Some(verdict @ Verdict::Fail { expected }) => (verdict_name(verdict), expected.as_str()),
+            Some(verdict) => (verdict_name(verdict), ""),
+        };
+        let alloc = match &self.alloc {
+            None => "\t\t".to_string(),
+            Some(alloc) => format!("{}\t{}\t{}", alloc.allocs, alloc.bytes, alloc.peak),
+        };
+        format!(
+            "{}\t{}\t{}\t{}\t{}\t{}\t{}",
+            self.day,
+            self.part,
+            tsv_escape(&self.answer.to_string()),
+            self.elapsed.as_nanos(),
+            verdict,
+            tsv_escape(expected),
+            alloc
+        )
+    }
+}
+
+/// How many of the slowest days the summary table highlights
+pub const SLOWEST: usize = 3;
+
+const ANSWER_WIDTH: usize = 24;
+
+/// A table of every record, marking the `SLOWEST` days by total time of their parts
+///
+/// Allocation columns are added when allocations were counted. With `color` the
+/// highlighted rows are also shown in bold red.
+pub fn summary_table(records: &[Record], color: bool) -> String {
+    let mut totals: BTreeMap<u8, Duration> = BTreeMap::new();
+    for record in records {
+        *totals.entry(record.day).or_default() += record.elapsed;
+    }
+    let mut by_time: Vec<(u8, Duration)> = totals.into_iter().collect();
+    by_time.sort_by_key(|&(day, total)| (std::cmp::Reverse(total), day));
+    let slowest: Vec<u8> = by_time.iter().take(SLOWEST).map(|&(day, _)| day).collect();
+
+    let show_alloc = records.iter().any(|record| record.alloc.is_some());
+    let alloc_columns = |allocs: &str, peak: &str| {
+        if show_alloc {
+            format!("{:>10}  {:>10}  ", allocs, peak)
+        } else {
+            String::new()
+        }
+    };
+
+    let mut table = format!(
+        "{:>3}  {:>4}  {:<width$}  {:>10}  {}{}\n",
+        "Day",
+        "Part",
+        "Answer",
+        "Time",
+        alloc_columns("Allocs", "Peak"),
+        "Verdict",
+        width = ANSWER_WIDTH
+    );
+    for record in records {
+        let answer = match &record.answer {
+            answer if answer.is_multiline() => format!("({} lines)", answer.to_string().lines().count()),
+            answer => {
+                let answer = answer.to_string();
+                if answer.chars().count() > ANSWER_WIDTH {
+                    format!("{}…", answer.chars().take(ANSWER_WIDTH - 1).collect::<String>())
+                } else {
+                    answer
+                }
+            }
+        };
+        let verdict = match &record.verdict {
+            None => "-".to_string(),
+            Some(Verdict::Fail { .. }) => "FAIL".to_string(),
+            Some(verdict) => verdict.to_string(),
+        };
+        let alloc = match &record.alloc {
+            Some(alloc) => alloc_columns(&alloc.allocs.to_string(), &format_bytes(alloc.peak)),
+            None => alloc_columns("-", "-"),
+        };
+        let mut row = format!(
+            "{:>3}  {:>4}  {:<width$}  {:>10}  {}{:<7}",
+            record.day,
+            record.part,
+            answer,
+            format_duration(record.elapsed),
+            alloc,
+            verdict,
+            width = ANSWER_WIDTH
+        );
+        if slowest.contains(&record.day) {
+            row.push_str("  ◀ slowest");
+            if color {
+                row = format!("\x1b[1;31m{}\x1b[0m", row);
+            }
+        }
+        table.push_str(row.trim_end());
+        table.push('\n');
+    }
+    table
+}
+
+fn verdict_name(verdict: &Verdict) -> &'static str {
+    match verdict {
+        Verdict::Pass => "pass",
+        Verdict::Fail { .. } => "fail",
+        Verdict::Missing => "missing",
+    }
+}
+
+/// `s` as a quoted JSON string
+pub fn json_string(s: &str) -> String {
+    let mut json = String::with_capacity(s.len() + 2);
+    json.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => json.push_str("\\\""),
+            '\\' => json.push_str("\\\\"),
+            '\n' => json.push_str("\\n"),
+            '\r' => json.push_str("\\r"),
+            '\t' => json.push_str("\\t"),
+            c if (c as u32) < 0x20 => json.push_str(&format!("\\u{:04x}", c as u32)),
+            c => json.push(c),
+        }
+    }
+    json.push('"');
+    json
+}
+
+/// Escape backslashes, tabs and line breaks so `s` fits in one TSV field
+pub fn tsv_escape(s: &str) -> String {
+    s.replace('\\', "\\\\")
+        .replace('\t', "\\t")
+        .replace('\n', "\\n")
+        .replace('\r', "\\r")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Undo `tsv_escape`
+    fn tsv_unescape(s: &str) -> String {
+        let mut text = String::with_capacity(s.len());
+        let mut chars = s.chars();
+        while let Some(c) = chars.next() {
+            if c != '\\' {
+                text.push(c);
+                continue;
+            }
+            match chars.next() {
+                Some('t') => text.push('\t'),
+                Some('n') => text.push('\n'),
+                Some('r') => text.push('\r'),
+                Some(c) => text.push(c),
+                None => text.push('\\'),
+            }
+        }
+        text
+    }
+
+    fn record(answer: Answer, verdict: Option<Verdict>) -> Record {
+        Record {
+            day: 10,
+            part: 1,
+            answer,
+            elapsed: Duration::from_nanos(1500),
+            verdict,
+            alloc: None,
+        }
+    }
+
+    #[test]
+    fn test_json() {
+        let integer = record(Answer::Integer(-42), None);
+        assert_eq!(integer.to_json(), r#"{"day":10,"part":1,"answer":-42,"elapsed_ns":1500}"#);
+
+        let grid = record(Answer::grid("#..#\n#\"\\#"), Some(Verdict::Pass));
+        assert_eq!(
+            grid.to_json(),
+            r##"{"day":10,"part":1,"answer":"#..#\n#\"\\#","elapsed_ns":1500,"verdict":"pass"}"##
+        );
+
+        let failed = record("CABDFE".into(), Some(Verdict::Fail { expected: "ABC".to_string() }));
+        assert!(failed.to_json().ends_with(r#""verdict":"fail","expected":"ABC"}"#));
+    }
+
+    #[test]
+    fn test_tsv_is_lossless() {
+        let text = "#..#\n#\t\\n#";
+        let line = record(Answer::grid(text), Some(Verdict::Missing)).to_tsv();
+        assert_eq!(line.lines().count(), 1);
+
+        let fields: Vec<&str> = line.split('\t').collect();
+        assert_eq!(fields.len(), TSV_HEADER.split('\t').count());
+        assert_eq!(fields[..2], ["10", "1"]);
+        assert_eq!(tsv_unescape(fields[2]), text);
+        assert_eq!(fields[3..], ["1500", "missing", "", "", "", ""]);
+    }
+
+    #[test]
+    fn test_alloc_stats_output() {
+        let mut counted = record(Answer::Integer(7), None);
+        counted.alloc = Some(AllocStats {
+            allocs: 12,
+            bytes: 4096,
+            peak: 1536,
+        });
+        assert_eq!(counted.to_text(), "Part 1: 7 (12 allocs, 4.0 KiB allocated, peak 1.5 KiB)");
+        assert!(counted.to_json().ends_with(r#","allocs":12,"alloc_bytes":4096,"peak_bytes":1536}"#));
+        assert!(counted.to_tsv().ends_with("\t12\t4096\t1536"));
+
+        let table = summary_table(&[counted], false);
+        assert!(table.lines().next().unwrap().contains("Allocs"));
+        assert!(table.contains("1.5 KiB"));
+    }
+
+    #[test]
+    fn test_text() {
+        assert_eq!(record(Answer::Integer(7), Some(Verdict::Pass)).to_text(), "Part 1: 7 [PASS]");
+        assert_eq!(record(Answer::grid("#.\n.#"), None).to_text(), "Part 1:\n#.\n.#");
+    }
+
+    #[test]
+    fn test_summary_table_marks_slowest_days() {
+        let mut records = Vec::new();
+        for (day, millis) in [(1, 5), (2, 1), (3, 9), (4, 2), (5, 7)] {
+            for part in 1..=2 {
+                records.push(Record {
+                    day,
+                    part,
+                    answer: Answer::Integer(day as i64),
+                    elapsed: Duration::from_millis(millis),
+                    verdict: (day == 1).then_some(Verdict::Pass),
+                    alloc: None,
+                });
+            }
+        }
+        records.push(Record {
+            day: 10,
+            part: 1,
+            answer: Answer::grid("#.\n.#\n##"),
+            elapsed: Duration::ZERO,
+            verdict: None,
+            alloc: None,
+        });
+
+        let table = summary_table(&records, false);
+        let lines: Vec<&str> = table.lines().collect();
+        assert_eq!(lines.len(), 12);
+        assert!(lines[0].starts_with("Day  Part  Answer"));
+        assert!(lines[1].contains("PASS") && lines[1].ends_with("◀ slowest"));
+        let marked: Vec<&str> = lines.iter().filter(|l| l.ends_with("◀ slowest")).map(|l| &l[..3]).collect();
+        assert_eq!(marked, ["  1", "  1", "  3", "  3", "  5", "  5"]);
+        assert!(lines[11].contains("(3 lines)"));
+        assert!(!table.contains('\x1b'));
+        assert!(summary_table(&records, true).contains("\x1b[1;31m"));
+    }
+
+    #[test]
+    fn test_parse_format() {
+        assert_eq!(Format::parse("json"), Ok(Format::Json));
+        assert!(Format::parse("csv").is_err());
+    }
+}
@@ -0,0 +1,181 @@
+//! Scaffolding of new days: rendering the day template and keeping the build
+//! registration consistent
+//!
+//! Days are discovered by `build.rs` from `src/dayNN.rs`, so a new day needs
+//! no registration of its own. What can still get in the way is a leftover
+//! per-day `[[bin]]` target in `Cargo.toml` from before the single runner.
+
+/// The template shipped with the crate, used when there is no `templates/day.rs`
+pub const DEFAULT_DAY_TEMPLATE: &str = include_str!("../templates/day.rs");
+
+/// Replace `{{name}}` placeholders in `template` with their values
+///
+/// An unknown or unterminated placeholder is an error, so a typo in a user
+/// template doesn't end up in the generated file.
+pub fn render_template(template: &str, values: &[(&str, &str)]) -> Result<String, String> {
+    let mut rendered = String::with_capacity(template.len());
+    let mut rest = template;
+
+    while let Some(start) = rest.find("{{") {
+        rendered.push_str(&rest[..start]);
+        let end = rest[start..]
+            .find("}}")
+            .map(|i| start + i)
+            .ok_or_else(|| "Unterminated {{ in template".to_string())?;
+        let name = rest[start + 2..end].trim();
+        let value = values
+            .iter()
+            .find(|(key, _)| *key == name)
+            .map(|(_, value)| *value)
+            .ok_or_else(|| {
+                let known: Vec<String> = values.iter().map(|(key, _)| format!("{{{{{}}}}}", key)).collect();
+                format!("Unknown placeholder {{{{{}}}}} in template (known: {})", name, known.join(", "))
+            })?;
+        rendered.push_str(value);
+        rest = &rest[end + 2..];
+    }
+    rendered.push_str(rest);
+    Ok(rendered)
+}
+
+/// Whether `build.rs` will register `source` as the module for `day`
+pub fn is_discoverable(source: &str, day: u8) -> bool {
+    source.contains(&format!("pub struct Day{:02}", day))
+}
+
+/// Remove `[[bin]]` sections of `Cargo.toml` that build `src/dayNN.rs` (or are named
+/// `dayNN`) for `day`, since that file is a library module
+///
+/// Returns the new manifest and the names of the removed targets; running it
+/// again on the result changes nothing.
+pub fn remove_day_bins(cargo_toml: &str, day: u8) -> (String, Vec<String>) {
+    let name = format!("day{:02}", day);
+    let path = format!("src/{}.rs", name);
+
+    // Split into sections, each starting at a `[header]` line
+    let mut sections: Vec<Vec<&str>> = vec![Vec::new()];
+    for line in cargo_toml.lines() {
+        if line.trim_start().starts_with('[') {
+            sections.push(Vec::new());
+        }
+        sections.last_mut().unwrap().push(line);
+    }
+
+    let value = |section: &[&str], key: &str| -> Option<String> {
+        section.iter().find_map(|line| {
+            let (k, v) = line.split_once('=')?;
+            (k.trim() == key).then(|| v.trim().trim_matches('"').to_string())
+        })
+    };
+
+    let mut removed = Vec::new();
+    let mut kept = Vec::new();
+    for section in sections {
+        let is_bin = section.first().is_some_and(|line| line.trim() == "[[bin]]");
+        let target_name = value(&section, "name");
+        let target_path = value(&section, "path");
+        if is_bin && (target_path.as_deref() == Some(path.as_str()) || target_name.as_deref() == Some(name.as_str())) {
+            removed.push(target_name.unwrap_or_else(|| path.clone()));
+        } else {
+            kept.push(section);
+        }
+    }
+
+    if removed.is_empty() {
+        return (cargo_toml.to_string(), removed);
+    }
+
+    let mut text = String::new();
+    for section in kept {
+        let lines = section.join("\n");
+        let lines = lines.trim_end();
+        if lines.is_empty() {
+            continue;
+        }
+        if !text.is_empty() {
+            text.push_str("\n\n");
+        }
+        text.push_str(lines);
+    }
+    text.push('\n');
+    (text, removed)
+}
+
+/// A day as `prepare` renders it from the default template, built and tested
+/// with the library so that generated days stay free of warnings
+///
+/// `tests/prepare.rs` checks that it still matches the template.
+#[cfg(test)]
+#[path = "../tests/fixtures/day03.rs"]
+mod rendered_day;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CARGO_TOML: &str = r#"[package]
+name = "aoc2018"
+version = "0.1.0"
+
+[[bin]]
+name = "aoc"
+path = "src/aoc.rs"
+
+[[bin]]
+name = "day03"
+path = "src/day03.rs"
+
+[[bin]]
+name = "day04"
+path = "src/day04.rs"
+
+[dependencies]
+ureq = "2.10"
+"#;
+
+    #[test]
+    fn test_remove_day_bins() {
+        let (text, removed) = remove_day_bins(CARGO_TOML, 3);
+        assert_eq!(removed, vec!["day03"]);
+        assert!(!text.contains("src/day03.rs"));
+        assert!(text.contains("src/day04.rs"));
+        assert!(text.contains("[[bin]]\nname = \"aoc\""));
+        assert!(text.contains("\n\n[dependencies]\nureq = \"2.10\"\n"));
+
+        let (again, removed) = remove_day_bins(&text, 3);
+        assert!(removed.is_empty());
+        assert_eq!(again, text);
+    }
+
+    #[test]
+    fn test_untouched_without_day_bins() {
+        let (text, removed) = remove_day_bins(CARGO_TOML, 7);
+        assert!(removed.is_empty());
+        assert_eq!(text, CARGO_TOML);
+    }
+
+    #[test]
+    fn test_render_template() {
+        let values = [("day", "7"), ("day_padded", "07")];
+        assert_eq!(
+            render_template("pub struct Day{{day_padded}}; // day {{ day }}", &values),
+            Ok("pub struct Day07; // day 7".to_string())
+        );
+        assert!(render_template("{{dya}}", &values).unwrap_err().contains("{{dya}}"));
+        assert!(render_template("{{day", &values).is_err());
+    }
+
+    #[test]
+    fn test_default_template_renders() {
+        let values = [("day", "7"), ("day_padded", "07"), ("year", "2018"), ("examples", "")];
+        let rendered = render_template(DEFAULT_DAY_TEMPLATE, &values).unwrap();
+        assert!(is_discoverable(&rendered, 7));
+        assert!(!rendered.contains("{{"));
+    }
+
+    #[test]
+    fn test_is_discoverable() {
+        assert!(is_discoverable("pub struct Day07;\n", 7));
+        assert!(!is_discoverable("pub struct Day7;\n", 7));
+    }
+}
@@ -0,0 +1,223 @@
+//! Graph searches over any state type: breadth-first, Dijkstra and A*
+//!
+//! The graph is given as a `neighbours` function from a state to the states
+//! next to it (with step costs for the weighted searches), so it is explored
+//! lazily and can be infinite as long as a goal is reached.
+//!
+//! Results are deterministic. Breadth-first search visits neighbours in the
+//! order `neighbours` returns them, so listing them in reading order gives
+//! reading-order tie-breaks. Dijkstra and A* settle states of equal cost in
+//! the order of `S`. In both, a state keeps the first predecessor that reached
+//! it at its final distance.
+
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashMap, VecDeque};
+use std::hash::Hash;
+
+/// Distances from the start of a search and the predecessor of each state
+#[derive(Debug, Clone)]
+pub struct Paths<S> {
+    start: S,
+    distances: HashMap<S, usize>,
+    predecessors: HashMap<S, S>,
+    goal: Option<S>,
+}
+
+impl<S: Clone + Eq + Hash> Paths<S> {
+    fn new(start: S) -> Self {
+        Paths {
+            distances: HashMap::from([(start.clone(), 0)]),
+            start,
+            predecessors: HashMap::new(),
+            goal: None,
+        }
+    }
+
+    pub fn start(&self) -> &S {
+        &self.start
+    }
+
+    /// Distance from the start to `state`, if it was reached
+    pub fn distance(&self, state: &S) -> Option<usize> {
+        self.distances.get(state).copied()
+    }
+
+    /// Distances of all reached states
+    ///
+    /// A search that stopped at its goal may hold states beyond it whose
+    /// distances are only upper bounds.
+    pub fn distances(&self) -> &HashMap<S, usize> {
+        &self.distances
+    }
+
+    pub fn into_distances(self) -> HashMap<S, usize> {
+        self.distances
+    }
+
+    /// The state `state` was reached from on a shortest path
+    pub fn predecessor(&self, state: &S) -> Option<&S> {
+        self.predecessors.get(state)
+    }
+
+    /// The first goal state the search reached, if it was given a goal
+    pub fn goal(&self) -> Option<&S> {
+        self.goal.as_ref()
+    }
+
+    /// Distance from the start to the goal
+    pub fn goal_distance(&self) -> Option<usize> {
+        self.distance(self.goal.as_ref()?)
+    }
+
+    /// The states on a shortest path from the start to `state`, both included
+    pub fn path(&self, state: &S) -> Option<Vec<S>> {
+        self.distances.get(state)?;
+        let mut path = vec![state.clone()];
+        while let Some(previous) = self.predecessors.get(path.last().unwrap()) {
+            path.push(previous.clone());
+        }
+        path.reverse();
+        Some(path)
+    }
+}
+
+/// Breadth-first search from `start`, stopping at the first state for which
+/// `is_goal` holds
+///
+/// Pass `|_| false` to explore everything reachable.
+pub fn bfs<S, I>(start: S, mut neighbours: impl FnMut(&S) -> I, mut is_goal: impl FnMut(&S) -> bool) -> Paths<S>
+where
+    S: Clone + Eq + Hash,
+    I: IntoIterator<Item = S>,
+{
+    let mut paths = Paths::new(start.clone());
+    let mut queue = VecDeque::from([start]);
+
+    while let Some(state) = queue.pop_front() {
+        if is_goal(&state) {
+            paths.goal = Some(state);
+            break;
+        }
+        let distance = paths.distances[&state] + 1;
+        for next in neighbours(&state) {
+            if !paths.distances.contains_key(&next) {
+                paths.distances.insert(next.clone(), distance);
+                paths.predecessors.insert(next.clone(), state.clone());
+                queue.push_back(next);
+            }
+        }
+    }
+    paths
+}
+
+/// Dijkstra's algorithm from `start`, where `neighbours` returns each next state
+/// with the cost of the step to it
+///
+/// Stops when a state for which `is_goal` holds is settled.
+pub fn dijkstra<S, I>(start: S, neighbours: impl FnMut(&S) -> I, is_goal: impl FnMut(&S) -> bool) -> Paths<S>
+where
+    S: Clone + Eq + Hash + Ord,
+    I: IntoIterator<Item = (S, usize)>,
+{
+    astar(start, neighbours, |_| 0, is_goal)
+}
+
+/// A* search from `start`, guided by a `heuristic` estimate of the remaining
+/// cost to a goal
+///
+/// The distance found is the shortest one as long as the heuristic never
+/// overestimates.
+pub fn astar<S, I>(
+    start: S,
+    mut neighbours: impl FnMut(&S) -> I,
+    mut heuristic: impl FnMut(&S) -> usize,
+    mut is_goal: impl FnMut(&S) -> bool,
+) -> Paths<S>
+where
+    S: Clone + Eq + Hash + Ord,
+    I: IntoIterator<Item = (S, usize)>,
+{
+    let mut paths = Paths::new(start.clone());
+    let mut heap = BinaryHeap::from([Reverse((heuristic(&start), 0, start))]);
+
+    while let Some(Reverse((_, distance, state))) = heap.pop() {
+        if distance > paths.distances[&state] {
+            continue;
+        }
+        if is_goal(&state) {
+            paths.goal = Some(state);
+            break;
+        }
+        for (next, cost) in neighbours(&state) {
+            let next_distance = distance + cost;
+            if paths.distances.get(&next).is_none_or(|&known| next_distance < known) {
+                paths.distances.insert(next.clone(), next_distance);
+                paths.predecessors.insert(next.clone(), state.clone());
+                heap.push(Reverse((next_distance + heuristic(&next), next_distance, next)));
+            }
+        }
+    }
+    paths
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::grid::Grid;
+
+    const MAZE: &str = "\
+.....
+.###.
+...#.
+.#...";
+
+    fn open_neighbours(maze: &Grid<bool>, pos: (usize, usize)) -> Vec<(usize, usize)> {
+        maze.neighbours4(pos).filter(|&next| maze[next]).collect()
+    }
+
+    fn maze() -> Grid<bool> {
+        Grid::parse(MAZE, |c| Some(c == '.')).unwrap()
+    }
+
+    #[test]
+    fn test_bfs() {
+        let maze = maze();
+        let paths = bfs((0, 0), |&pos| open_neighbours(&maze, pos), |_| false);
+        assert_eq!(paths.distance(&(4, 3)), Some(7));
+        assert_eq!(paths.distance(&(2, 2)), Some(4));
+        assert_eq!(paths.distance(&(1, 1)), None);
+        assert_eq!(paths.distances().len(), 15);
+
+        // Both ways around to (4, 3) are as short; the first neighbour wins
+        let path = paths.path(&(4, 3)).unwrap();
+        assert_eq!(path.first(), Some(&(0, 0)));
+        assert_eq!(path[1], (1, 0));
+        assert_eq!(path.len(), 8);
+
+        let paths = bfs((0, 0), |&pos| open_neighbours(&maze, pos), |&pos| pos == (0, 3));
+        assert_eq!(paths.goal(), Some(&(0, 3)));
+        assert_eq!(paths.goal_distance(), Some(3));
+    }
+
+    #[test]
+    fn test_dijkstra_and_astar_agree() {
+        let maze = maze();
+        // Moving right along the top row is expensive
+        let weighted = |&(x, y): &(usize, usize)| {
+            open_neighbours(&maze, (x, y))
+                .into_iter()
+                .map(move |next| (next, if next.0 > x && y == 0 { 3 } else { 1 }))
+        };
+        let goal = (4, 3);
+
+        let dijkstra = dijkstra((0, 0), weighted, |&pos| pos == goal);
+        assert_eq!(dijkstra.goal_distance(), Some(7));
+        assert_eq!(dijkstra.path(&goal).unwrap()[1], (0, 1));
+
+        let manhattan = |&(x, y): &(usize, usize)| goal.0.abs_diff(x) + goal.1.abs_diff(y);
+        let astar = astar((0, 0), weighted, manhattan, |&pos| pos == goal);
+        assert_eq!(astar.goal_distance(), Some(7));
+        assert_eq!(astar.path(&goal), dijkstra.path(&goal));
+        assert!(astar.distances().len() <= dijkstra.distances().len());
+    }
+}
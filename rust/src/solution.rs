@@ -0,0 +1,148 @@
+use crate::error::Result;
+use std::any::Any;
+use std::fmt;
+
+/// An answer to one part of a puzzle
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Answer {
+    Integer(i64),
+    Text(String),
+    /// A multi-line answer such as a rendered message, one row per entry
+    Grid(Vec<String>),
+}
+
+impl Answer {
+    /// Build a grid answer from rendered text, one row per line
+    pub fn grid(text: &str) -> Self {
+        Answer::Grid(text.lines().map(str::to_string).collect())
+    }
+
+    pub fn is_multiline(&self) -> bool {
+        matches!(self, Answer::Grid(rows) if rows.len() > 1)
+    }
+}
+
+impl fmt::Display for Answer {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Answer::Integer(n) => write!(f, "{}", n),
+            Answer::Text(s) => write!(f, "{}", s),
+            Answer::Grid(rows) => write!(f, "{}", rows.join("\n")),
+        }
+    }
+}
+
+macro_rules! integer_answer {
+    ($($ty:ty),*) => {
+        $(
+            impl From<$ty> for Answer {
+                fn from(n: $ty) -> Self {
+                    Answer::Integer(n as i64)
+                }
+            }
+        )*
+    };
+}
+
+integer_answer!(i32, i64, u32, usize);
+
+impl From<String> for Answer {
+    fn from(s: String) -> Self {
+        Answer::Text(s)
+    }
+}
+
+impl From<&str> for Answer {
+    fn from(s: &str) -> Self {
+        Answer::Text(s.to_string())
+    }
+}
+
+/// A day's puzzle: parse the input once, then answer both parts from it
+pub trait Solution {
+    type Input: 'static;
+
+    fn parse(&self, input: &str) -> Result<Self::Input>;
+    fn part1(&self, input: &Self::Input) -> Answer;
+    fn part2(&self, input: &Self::Input) -> Answer;
+
+    /// Example inputs from the puzzle description
+    fn examples(&self) -> &'static [&'static str] {
+        &[]
+    }
+}
+
+/// Object-safe view of a `Solution`, used by the day registry
+pub trait DynSolution: Sync {
+    fn parse(&self, input: &str) -> Result<Box<dyn Any>>;
+    fn part(&self, part: u8, parsed: &dyn Any) -> Answer;
+    fn examples(&self) -> &'static [&'static str];
+}
+
+impl<S: Solution + Sync> DynSolution for S {
+    fn parse(&self, input: &str) -> Result<Box<dyn Any>> {
+        Ok(Box::new(Solution::parse(self, input)?))
+    }
+
+    fn part(&self, part: u8, parsed: &dyn Any) -> Answer {
+        let input = parsed
+            .downcast_ref::<S::Input>()
+            .expect("Parsed input belongs to another solution");
+        match part {
+            1 => self.part1(input),
+            2 => self.part2(input),
+            _ => panic!("Invalid part: {}", part),
+        }
+    }
+
+    fn examples(&self) -> &'static [&'static str] {
+        Solution::examples(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::parse_at;
+
+    struct Sum;
+
+    impl Solution for Sum {
+        type Input = Vec<i64>;
+
+        fn parse(&self, input: &str) -> Result<Vec<i64>> {
+            input.lines().map(|l| parse_at(input, l)).collect()
+        }
+
+        fn part1(&self, numbers: &Vec<i64>) -> Answer {
+            numbers.iter().sum::<i64>().into()
+        }
+
+        fn part2(&self, numbers: &Vec<i64>) -> Answer {
+            format!("{} numbers", numbers.len()).into()
+        }
+    }
+
+    #[test]
+    fn test_answer_display() {
+        assert_eq!(Answer::from(42usize).to_string(), "42");
+        assert_eq!(Answer::from("33,45").to_string(), "33,45");
+        assert_eq!(Answer::grid("#..\n.#.\n").to_string(), "#..\n.#.");
+        assert!(Answer::grid("#..\n.#.\n").is_multiline());
+        assert!(!Answer::from(-3).is_multiline());
+    }
+
+    #[test]
+    fn test_answer_equality_across_integer_types() {
+        assert_eq!(Answer::from(7i32), Answer::from(7usize));
+    }
+
+    #[test]
+    fn test_dyn_solution_dispatch() {
+        let solution: &dyn DynSolution = &Sum;
+        let parsed = solution.parse("1\n2\n3").unwrap();
+        assert_eq!(solution.part(1, parsed.as_ref()), Answer::Integer(6));
+        assert_eq!(solution.part(2, parsed.as_ref()), Answer::Text("3 numbers".to_string()));
+        assert!(solution.parse("1\nx").is_err());
+    }
+}
@@ -0,0 +1,333 @@
+//! Outcomes of answer submissions and the local log of them
+
+use crate::AocError;
+use std::fmt;
+use std::fs::{self, OpenOptions};
+use std::io::{self, Write};
+use std::path::PathBuf;
+
+/// What Advent of Code said about a submitted answer
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Outcome {
+    Correct,
+    TooHigh,
+    TooLow,
+    /// Wrong, without a hint about the direction
+    Wrong,
+    /// Submitted too soon after the previous answer; nothing was checked
+    Wait { seconds: u64 },
+    /// The part is already solved, or not unlocked yet
+    WrongLevel,
+    Unknown,
+}
+
+impl Outcome {
+    /// Classify the HTML response to an answer submission
+    pub fn parse(response: &str) -> Self {
+        if response.contains("That's the right answer") {
+            Outcome::Correct
+        } else if response.contains("your answer is too high") {
+            Outcome::TooHigh
+        } else if response.contains("your answer is too low") {
+            Outcome::TooLow
+        } else if response.contains("That's not the right answer") {
+            Outcome::Wrong
+        } else if response.contains("You gave an answer too recently") {
+            Outcome::Wait {
+                seconds: wait_seconds(response).unwrap_or(60),
+            }
+        } else if response.contains("You don't seem to be solving the right level") {
+            Outcome::WrongLevel
+        } else {
+            Outcome::Unknown
+        }
+    }
+
+    /// The answer is known to be wrong and must not be sent again
+    pub fn is_wrong(self) -> bool {
+        matches!(self, Outcome::TooHigh | Outcome::TooLow | Outcome::Wrong)
+    }
+
+    fn keyword(self) -> &'static str {
+        match self {
+            Outcome::Correct => "correct",
+            Outcome::TooHigh => "too-high",
+            Outcome::TooLow => "too-low",
+            Outcome::Wrong => "wrong",
+            Outcome::Wait { .. } => "wait",
+            Outcome::WrongLevel => "wrong-level",
+            Outcome::Unknown => "unknown",
+        }
+    }
+
+    fn from_keyword(keyword: &str) -> Option<Self> {
+        Some(match keyword {
+            "correct" => Outcome::Correct,
+            "too-high" => Outcome::TooHigh,
+            "too-low" => Outcome::TooLow,
+            "wrong" => Outcome::Wrong,
+            "wait" => Outcome::Wait { seconds: 0 },
+            "wrong-level" => Outcome::WrongLevel,
+            "unknown" => Outcome::Unknown,
+            _ => return None,
+        })
+    }
+}
+
+impl fmt::Display for Outcome {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Outcome::Correct => write!(f, "That's the right answer!"),
+            Outcome::TooHigh => write!(f, "Wrong: the answer is too high"),
+            Outcome::TooLow => write!(f, "Wrong: the answer is too low"),
+            Outcome::Wrong => write!(f, "Wrong answer"),
+            Outcome::Wait { seconds } => write!(
+                f,
+                "Submitted too recently, wait {}m {}s before trying again",
+                seconds / 60,
+                seconds % 60
+            ),
+            Outcome::WrongLevel => write!(f, "This part is already solved or not unlocked yet"),
+            Outcome::Unknown => write!(f, "Unrecognized response"),
+        }
+    }
+}
+
+/// How long the server says to wait before the next submission: "You have 4m
+/// 2s left to wait" after answering too soon, or "Please wait one minute" and
+/// "please wait 5 minutes" in the lockout after a wrong answer
+pub fn wait_seconds(response: &str) -> Option<u64> {
+    time_left(response).or_else(|| lockout(response))
+}
+
+/// Seconds in "You have 4m 2s left to wait"
+fn time_left(response: &str) -> Option<u64> {
+    let start = response.find("You have ")? + "You have ".len();
+    let end = response[start..].find(" left to wait")? + start;
+
+    let mut seconds = 0;
+    for amount in response[start..end].split_whitespace() {
+        let unit = amount.chars().last()?;
+        let value: u64 = amount.strip_suffix(['h', 'm', 's'])?.parse().ok()?;
+        seconds += match unit {
+            'h' => value * 3600,
+            'm' => value * 60,
+            _ => value,
+        };
+    }
+    Some(seconds)
+}
+
+/// Seconds in "wait one minute" or "wait 5 minutes"
+fn lockout(response: &str) -> Option<u64> {
+    let start = response.find("wait ")? + "wait ".len();
+    let mut words = response[start..].split_whitespace();
+    let minutes = match words.next()? {
+        "one" => 1,
+        count => count.parse().ok()?,
+    };
+    matches!(words.next()?, "minute" | "minutes" | "minute." | "minutes.").then_some(minutes * 60)
+}
+
+/// One logged submission
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Submission {
+    /// Seconds since the epoch
+    pub timestamp: u64,
+    pub part: u8,
+    pub answer: String,
+    pub outcome: Outcome,
+    /// Seconds since the epoch until which the server refuses submissions
+    pub wait_until: Option<u64>,
+}
+
+/// Submissions for one day, stored in `answers/YYYY/dayNN.log` as tab separated
+/// `timestamp part answer outcome` lines, followed by a `wait_until` field
+/// when the server asked to wait
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SubmissionLog {
+    pub submissions: Vec<Submission>,
+}
+
+impl SubmissionLog {
+    pub fn parse(text: &str) -> Self {
+        let submissions = text
+            .lines()
+            .filter_map(|line| {
+                let mut fields = line.split('\t');
+                let timestamp = fields.next()?.parse().ok()?;
+                let part = fields.next()?.parse().ok()?;
+                let answer = fields.next()?.to_string();
+                let mut outcome = Outcome::from_keyword(fields.next()?)?;
+                let wait_until: Option<u64> = fields.next().and_then(|field| field.parse().ok());
+                if let (Outcome::Wait { seconds }, Some(until)) = (&mut outcome, wait_until) {
+                    *seconds = until.saturating_sub(timestamp);
+                }
+                Some(Submission {
+                    timestamp,
+                    part,
+                    answer,
+                    outcome,
+                    wait_until,
+                })
+            })
+            .collect();
+        SubmissionLog { submissions }
+    }
+
+    /// Why `answer` must not be submitted for `part` at `now` (seconds since the
+    /// epoch), if it is known to be wrong or the server would refuse it
+    ///
+    /// Besides exact repeats, a number at or beyond a bound reported as too
+    /// high or too low is rejected, and so is anything before the wait the
+    /// server asked for after the last submission has passed.
+    pub fn rejection(&self, part: u8, answer: &str, now: u64) -> Option<String> {
+        let number: Option<i64> = answer.parse().ok();
+
+        for submission in self.submissions.iter().filter(|s| s.part == part) {
+            if submission.outcome == Outcome::Correct {
+                return Some(format!("part {} was already solved with {}", part, submission.answer));
+            }
+            if submission.outcome.is_wrong() && submission.answer == answer {
+                return Some(format!("{} was already submitted: {}", answer, submission.outcome));
+            }
+
+            let bound: Option<i64> = submission.answer.parse().ok();
+            match (number, bound, submission.outcome) {
+                (Some(n), Some(high), Outcome::TooHigh) if n >= high => {
+                    return Some(format!("{} was too high, so {} is too", high, n))
+                }
+                (Some(n), Some(low), Outcome::TooLow) if n <= low => {
+                    return Some(format!("{} was too low, so {} is too", low, n))
+                }
+                _ => {}
+            }
+        }
+
+        let wait_until = self.submissions.iter().filter_map(|s| s.wait_until).max()?;
+        let left = wait_until.checked_sub(now).filter(|&left| left > 0)?;
+        Some(format!("the server asked to wait, {}m {}s left", left / 60, left % 60))
+    }
+}
+
+fn log_path(year: u16, day: u8) -> PathBuf {
+    PathBuf::from(format!("answers/{}/day{:02}.log", year, day))
+}
+
+/// Load the submission log for a day; a missing file means nothing was submitted yet
+pub fn load_log(year: u16, day: u8) -> Result<SubmissionLog, AocError> {
+    let path = log_path(year, day);
+    match fs::read_to_string(&path) {
+        Ok(text) => Ok(SubmissionLog::parse(&text)),
+        Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(SubmissionLog::default()),
+        Err(e) => Err(AocError::io(path, e)),
+    }
+}
+
+/// Append a submission to the day's log
+pub fn log_submission(year: u16, day: u8, submission: &Submission) -> Result<(), AocError> {
+    let path = log_path(year, day);
+    let dir = path.parent().unwrap();
+    fs::create_dir_all(dir).map_err(|e| AocError::io(dir, e))?;
+    let mut line = format!(
+        "{}\t{}\t{}\t{}",
+        submission.timestamp,
+        submission.part,
+        submission.answer,
+        submission.outcome.keyword()
+    );
+    if let Some(until) = submission.wait_until {
+        line += &format!("\t{}", until);
+    }
+    line.push('\n');
+
+    OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(&path)
+        .and_then(|mut file| file.write_all(line.as_bytes()))
+        .map_err(|e| AocError::io(path, e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_outcome() {
+        let page = |message: &str| format!("<main>\n<article><p>{}</p></article>\n</main>", message);
+        assert_eq!(
+            Outcome::parse(&page("That's the right answer!  You are one gold star closer.")),
+            Outcome::Correct
+        );
+        assert_eq!(
+            Outcome::parse(&page("That's not the right answer; your answer is too high.  Please wait one minute.")),
+            Outcome::TooHigh
+        );
+        assert_eq!(
+            Outcome::parse(&page("That's not the right answer; your answer is too low.")),
+            Outcome::TooLow
+        );
+        assert_eq!(Outcome::parse(&page("That's not the right answer.")), Outcome::Wrong);
+        assert_eq!(
+            Outcome::parse(&page("You gave an answer too recently.  You have 4m 2s left to wait.")),
+            Outcome::Wait { seconds: 242 }
+        );
+        assert_eq!(
+            Outcome::parse(&page("You don't seem to be solving the right level.  Did you already complete it?")),
+            Outcome::WrongLevel
+        );
+        assert_eq!(Outcome::parse("<html></html>"), Outcome::Unknown);
+    }
+
+    #[test]
+    fn test_log_round_trip_and_rejection() {
+        let log = SubmissionLog::parse("1700000000\t1\t500\ttoo-high\n1700000100\t1\t20\ttoo-low\n1700000200\t1\t77\twrong\n");
+        assert_eq!(log.submissions.len(), 3);
+        assert_eq!(log.submissions[1].outcome, Outcome::TooLow);
+
+        let now = 1700001000;
+        assert!(log.rejection(1, "77", now).is_some());
+        assert!(log.rejection(1, "500", now).is_some());
+        assert!(log.rejection(1, "612", now).is_some());
+        assert!(log.rejection(1, "3", now).is_some());
+        assert_eq!(log.rejection(1, "100", now), None);
+        assert_eq!(log.rejection(2, "77", now), None);
+    }
+
+    #[test]
+    fn test_rejects_solved_part() {
+        let log = SubmissionLog::parse("1700000000\t2\tCABDFE\tcorrect\n");
+        assert!(log.rejection(2, "ABC", 1700000000).unwrap().contains("already solved"));
+    }
+
+    #[test]
+    fn test_wait_seconds() {
+        assert_eq!(wait_seconds("You have 1h 4m 2s left to wait."), Some(3842));
+        assert_eq!(
+            wait_seconds("That's not the right answer.  Please wait one minute before trying again."),
+            Some(60)
+        );
+        assert_eq!(wait_seconds("because you have guessed incorrectly 4 times, please wait 5 minutes"), Some(300));
+        assert_eq!(wait_seconds("That's the right answer!"), None);
+        assert_eq!(wait_seconds("You have 4m 2é left to wait."), None);
+        assert_eq!(wait_seconds("You have 4m s left to wait."), None);
+    }
+
+    #[test]
+    fn test_rejects_until_wait_has_passed() {
+        // A wrong answer with a minute of lockout, then one sent too early
+        let log = SubmissionLog::parse("1700000000\t1\t77\twrong\t1700000060\n1700000010\t1\t78\twait\t1700000252\n");
+        assert_eq!(log.submissions[0].wait_until, Some(1700000060));
+        assert_eq!(log.submissions[1].outcome, Outcome::Wait { seconds: 242 });
+
+        let reason = log.rejection(1, "80", 1700000050).unwrap();
+        assert!(reason.contains("3m 22s left"), "{}", reason);
+        assert!(log.rejection(2, "80", 1700000251).is_some());
+        assert_eq!(log.rejection(1, "80", 1700000252), None);
+
+        // A lockout longer than the later wait still counts
+        let log = SubmissionLog::parse("1700000000\t1\t77\twrong\t1700000300\n1700000010\t1\t78\twait\t1700000060\n");
+        assert!(log.rejection(1, "80", 1700000100).is_some());
+    }
+}
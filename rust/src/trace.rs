@@ -0,0 +1,205 @@
+//! Leveled tracing for the solvers, switched on with `AOC_TRACE`
+//!
+//! `AOC_TRACE` is a comma-separated list of `target=level` directives, such as
+//! `day16=debug,day21=trace`. A bare level applies to every day and a bare target
+//! means `debug` for it. Targets are the day modules, e.g. `day16`, and the
+//! levels are `error`, `warn`, `info`, `debug` and `trace`. Messages go to stderr.
+//!
+//! Solvers log with the `info!`, `debug!` and `trace!` macros, which don't
+//! evaluate their arguments unless the level is enabled.
+
+use std::env;
+use std::fmt;
+use std::sync::atomic::{AtomicU8, Ordering};
+use std::sync::OnceLock;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Level {
+    Error,
+    Warn,
+    Info,
+    Debug,
+    Trace,
+}
+
+impl Level {
+    pub fn parse(name: &str) -> Option<Self> {
+        match name.to_ascii_lowercase().as_str() {
+            "error" => Some(Level::Error),
+            "warn" => Some(Level::Warn),
+            "info" => Some(Level::Info),
+            "debug" => Some(Level::Debug),
+            "trace" => Some(Level::Trace),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for Level {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let name = match self {
+            Level::Error => "ERROR",
+            Level::Warn => "WARN",
+            Level::Info => "INFO",
+            Level::Debug => "DEBUG",
+            Level::Trace => "TRACE",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+/// The most verbose level enabled for each target
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Filter {
+    /// Level for targets without a directive of their own
+    default: Option<Level>,
+    targets: Vec<(String, Level)>,
+}
+
+impl Filter {
+    /// Parse an `AOC_TRACE` value
+    pub fn parse(spec: &str) -> Result<Self, String> {
+        let mut filter = Filter::default();
+        for directive in spec.split(',').map(str::trim).filter(|d| !d.is_empty()) {
+            match directive.split_once('=') {
+                Some((target, level)) => {
+                    let level = Level::parse(level.trim())
+                        .ok_or_else(|| format!("Unknown trace level {:?} in {:?}", level, directive))?;
+                    filter.targets.push((target.trim().to_string(), level));
+                }
+                None => match Level::parse(directive) {
+                    Some(level) => filter.default = Some(level),
+                    None => filter.targets.push((directive.to_string(), Level::Debug)),
+                },
+            }
+        }
+        Ok(filter)
+    }
+
+    /// Whether messages at `level` from `target` are shown
+    pub fn enabled(&self, level: Level, target: &str) -> bool {
+        let max = self
+            .targets
+            .iter()
+            .rev()
+            .find(|(name, _)| name == target)
+            .map(|&(_, level)| level)
+            .or(self.default);
+        max.is_some_and(|max| level <= max)
+    }
+
+    /// The most verbose level enabled for any target
+    pub fn max_level(&self) -> Option<Level> {
+        self.targets.iter().map(|&(_, level)| level).chain(self.default).max()
+    }
+}
+
+/// `MAX_LEVEL` before `AOC_TRACE` has been read
+const UNINITIALIZED: u8 = u8::MAX;
+
+/// The filter's `max_level` as 0 for none and `level as u8 + 1` otherwise, so
+/// that untraced runs get past the macros with one load
+static MAX_LEVEL: AtomicU8 = AtomicU8::new(UNINITIALIZED);
+
+fn filter() -> &'static Filter {
+    static FILTER: OnceLock<Filter> = OnceLock::new();
+    FILTER.get_or_init(|| {
+        let spec = env::var("AOC_TRACE").unwrap_or_default();
+        let filter = Filter::parse(&spec).unwrap_or_else(|e| {
+            eprintln!("Ignoring AOC_TRACE: {}", e);
+            Filter::default()
+        });
+        MAX_LEVEL.store(filter.max_level().map_or(0, |level| level as u8 + 1), Ordering::Relaxed);
+        filter
+    })
+}
+
+/// Whether `level` is enabled for any target, the cheap check the macros make
+/// before looking at the module
+#[inline]
+pub fn any_enabled(level: Level) -> bool {
+    let max = MAX_LEVEL.load(Ordering::Relaxed);
+    if max == UNINITIALIZED {
+        return filter().max_level().is_some_and(|max| level <= max);
+    }
+    (level as u8) < max
+}
+
+/// The target of a module path: its last segment, e.g. `day16`
+pub fn target(module_path: &str) -> &str {
+    module_path.rsplit("::").next().unwrap_or(module_path)
+}
+
+/// Whether `AOC_TRACE` enables `level` for the module at `module_path`
+pub fn enabled(level: Level, module_path: &str) -> bool {
+    filter().enabled(level, target(module_path))
+}
+
+/// Write one message to stderr; use the macros instead
+pub fn log(level: Level, module_path: &str, message: fmt::Arguments) {
+    eprintln!("[{} {}] {}", target(module_path), level, message);
+}
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! log_at {
+    ($level:expr, $($arg:tt)*) => {
+        if $crate::trace::any_enabled($level) && $crate::trace::enabled($level, module_path!()) {
+            $crate::trace::log($level, module_path!(), format_args!($($arg)*));
+        }
+    };
+}
+
+/// Log progress worth seeing in a normal traced run
+#[macro_export]
+macro_rules! info {
+    ($($arg:tt)*) => { $crate::log_at!($crate::trace::Level::Info, $($arg)*) };
+}
+
+/// Log a decision a solver makes
+#[macro_export]
+macro_rules! debug {
+    ($($arg:tt)*) => { $crate::log_at!($crate::trace::Level::Debug, $($arg)*) };
+}
+
+/// Log details of every step
+#[macro_export]
+macro_rules! trace {
+    ($($arg:tt)*) => { $crate::log_at!($crate::trace::Level::Trace, $($arg)*) };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_filter() {
+        let filter = Filter::parse("day16=debug, day21=TRACE,day15").unwrap();
+        assert!(filter.enabled(Level::Debug, "day16"));
+        assert!(!filter.enabled(Level::Trace, "day16"));
+        assert!(filter.enabled(Level::Trace, "day21"));
+        assert!(filter.enabled(Level::Debug, "day15"));
+        assert!(!filter.enabled(Level::Error, "day01"));
+
+        let filter = Filter::parse("info,day18=off").unwrap_err();
+        assert!(filter.contains("off"));
+    }
+
+    #[test]
+    fn test_default_level() {
+        let filter = Filter::parse("info,day18=trace").unwrap();
+        assert!(filter.enabled(Level::Info, "day01"));
+        assert!(!filter.enabled(Level::Debug, "day01"));
+        assert!(filter.enabled(Level::Trace, "day18"));
+        assert_eq!(filter.max_level(), Some(Level::Trace));
+        assert_eq!(Filter::parse("day16=info").unwrap().max_level(), Some(Level::Info));
+        assert_eq!(Filter::parse("").unwrap(), Filter::default());
+        assert_eq!(Filter::default().max_level(), None);
+    }
+
+    #[test]
+    fn test_target() {
+        assert_eq!(target("aoc2018::day16"), "day16");
+        assert_eq!(target("aoc"), "aoc");
+    }
+}
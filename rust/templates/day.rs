@@ -0,0 +1,37 @@
+use crate::error::Result;
+use crate::{Answer, Solution};
+
+pub struct Day{{day_padded}};
+
+impl Solution for Day{{day_padded}} {
+    type Input = Vec<String>;
+
+    fn parse(&self, input: &str) -> Result<Vec<String>> {
+        Ok(input.lines().map(str::to_string).collect())
+    }
+
+    fn part1(&self, lines: &Vec<String>) -> Answer {
+        part1(lines).into()
+    }
+
+    fn part2(&self, lines: &Vec<String>) -> Answer {
+        part2(lines).into()
+    }
+}
+
+fn part1(_lines: &[String]) -> i32 {
+    // TODO: Implement part 1
+    0
+}
+
+fn part2(_lines: &[String]) -> i32 {
+    // TODO: Implement part 2
+    0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+{{examples}}
+}
@@ -0,0 +1,28 @@
+//! The alternate day 15 solutions in `alternates/`, built here so they keep
+//! compiling and checked against the registered solution on the examples
+
+#[path = "../alternates/day15_v2.rs"]
+mod v2;
+#[path = "../alternates/day15_v3.rs"]
+mod v3;
+
+use aoc2018::day15::Day15;
+use aoc2018::{find_day, Answer, Solution};
+
+fn registered(part: u8, input: &str) -> i32 {
+    match find_day(15).unwrap().run(part, input).unwrap() {
+        Answer::Integer(answer) => answer as i32,
+        answer => panic!("unexpected answer {:?}", answer),
+    }
+}
+
+#[test]
+fn test_alternates_agree_on_examples() {
+    for example in Day15.examples() {
+        for (part, v2, v3) in [(1, v2::part1 as fn(&str) -> i32, v3::part1 as fn(&str) -> i32), (2, v2::part2, v3::part2)] {
+            let expected = registered(part, example);
+            assert_eq!(v2(example), expected, "v2 part {} of\n{}", part, example);
+            assert_eq!(v3(example), expected, "v3 part {} of\n{}", part, example);
+        }
+    }
+}
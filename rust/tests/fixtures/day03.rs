@@ -0,0 +1,49 @@
+use crate::error::Result;
+use crate::{Answer, Solution};
+
+pub struct Day03;
+
+impl Solution for Day03 {
+    type Input = Vec<String>;
+
+    fn parse(&self, input: &str) -> Result<Vec<String>> {
+        Ok(input.lines().map(str::to_string).collect())
+    }
+
+    fn part1(&self, lines: &Vec<String>) -> Answer {
+        part1(lines).into()
+    }
+
+    fn part2(&self, lines: &Vec<String>) -> Answer {
+        part2(lines).into()
+    }
+}
+
+fn part1(_lines: &[String]) -> i32 {
+    // TODO: Implement part 1
+    0
+}
+
+fn part2(_lines: &[String]) -> i32 {
+    // TODO: Implement part 2
+    0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_part1() {
+        let input = r"1
+2
+";
+        assert_eq!(part1(&Day03.parse(input).unwrap()).to_string(), "0");
+    }
+
+    #[test]
+    fn test_part2() {
+        let input = "";
+        assert_eq!(part2(&Day03.parse(input).unwrap()), 0);
+    }
+}
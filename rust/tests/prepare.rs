@@ -0,0 +1,491 @@
+//! End-to-end tests of the `prepare` binary against a local stand-in for adventofcode.com
+
+use std::fs;
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::TcpListener;
+use std::ops::Deref;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Output, Stdio};
+use std::sync::{Arc, Mutex};
+use std::thread;
+
+const SESSION: &str = "good-session";
+
+const PUZZLE: &str = r#"<main>
+<article class="day-desc"><h2>--- Day 3: Test ---</h2><p>For example:</p>
+<pre><code>1
+2
+</code></pre>
+<p>The answer is <code><em>3</em></code>.</p>
+</article>
+</main>"#;
+
+/// A request as seen by the stand-in server
+#[derive(Debug, Clone)]
+struct Request {
+    method: String,
+    path: String,
+    cookie: Option<String>,
+    user_agent: Option<String>,
+    body: String,
+}
+
+/// Serve `respond(request) -> (status, body)` on a local port until the test ends
+fn serve(respond: fn(&Request) -> (u16, String)) -> (String, Arc<Mutex<Vec<Request>>>) {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let url = format!("http://{}", listener.local_addr().unwrap());
+    let requests = Arc::new(Mutex::new(Vec::new()));
+    let seen = Arc::clone(&requests);
+
+    thread::spawn(move || {
+        for stream in listener.incoming() {
+            let Ok(mut stream) = stream else { continue };
+            let mut reader = BufReader::new(stream.try_clone().unwrap());
+
+            let mut line = String::new();
+            reader.read_line(&mut line).unwrap();
+            let mut request_line = line.split_whitespace();
+            let method = request_line.next().unwrap_or_default().to_string();
+            let path = request_line.next().unwrap_or_default().to_string();
+
+            let mut cookie = None;
+            let mut user_agent = None;
+            let mut length = 0;
+            loop {
+                line.clear();
+                if reader.read_line(&mut line).unwrap() == 0 || line.trim().is_empty() {
+                    break;
+                }
+                let (name, value) = line.trim().split_once(": ").unwrap_or_default();
+                match name.to_ascii_lowercase().as_str() {
+                    "cookie" => cookie = Some(value.to_string()),
+                    "user-agent" => user_agent = Some(value.to_string()),
+                    "content-length" => length = value.parse().unwrap(),
+                    _ => {}
+                }
+            }
+            let mut body = vec![0; length];
+            reader.read_exact(&mut body).unwrap();
+
+            let request = Request {
+                method,
+                path,
+                cookie,
+                user_agent,
+                body: String::from_utf8_lossy(&body).into_owned(),
+            };
+            let (status, body) = respond(&request);
+            seen.lock().unwrap().push(request);
+            let response = format!(
+                "HTTP/1.1 {} Status\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                status,
+                body.len(),
+                body
+            );
+            stream.write_all(response.as_bytes()).unwrap();
+        }
+    });
+    (url, requests)
+}
+
+/// Like adventofcode.com: 400 without a valid session, 404 for days that don't exist
+fn site(request: &Request) -> (u16, String) {
+    if request.cookie.as_deref() != Some(&format!("session={}", SESSION)) {
+        return (400, "Puzzle inputs differ by user.  Please log in to get your puzzle input.".to_string());
+    }
+    match request.path.as_str() {
+        "/2018/day/3/input" => (200, "1\n2\n".to_string()),
+        "/2018/day/3" => (200, PUZZLE.to_string()),
+        _ => (404, "404 Not Found".to_string()),
+    }
+}
+
+/// An empty working directory for one test, removed when dropped
+struct Workdir(PathBuf);
+
+impl Deref for Workdir {
+    type Target = Path;
+
+    fn deref(&self) -> &Path {
+        &self.0
+    }
+}
+
+impl Drop for Workdir {
+    fn drop(&mut self) {
+        let _ = fs::remove_dir_all(&self.0);
+    }
+}
+
+fn workdir(name: &str) -> Workdir {
+    let dir = std::env::temp_dir().join(format!("aoc2018-prepare-{}-{}", name, std::process::id()));
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir).unwrap();
+    Workdir(dir)
+}
+
+fn prepare(dir: &Path, base_url: &str, session: &str, args: &[&str], stdin: &str) -> Output {
+    let mut command = Command::new(env!("CARGO_BIN_EXE_prepare"));
+    // Keep the developer's own settings out of the tests
+    for setting in ["AOC_SESSION", "AOC_YEAR", "AOC_USER_AGENT", "AOC_INPUT_DIR"] {
+        command.env_remove(setting);
+    }
+    if !args.contains(&"--request-interval") {
+        command.env("AOC_REQUEST_INTERVAL", "0");
+    }
+    if !session.is_empty() {
+        command.env("AOC_SESSION", session);
+    }
+
+    let mut child = command
+        .args(args)
+        .current_dir(dir)
+        .env("AOC_BASE_URL", base_url)
+        .env("XDG_CONFIG_HOME", dir.join("config"))
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .unwrap();
+    child.stdin.take().unwrap().write_all(stdin.as_bytes()).unwrap();
+    child.wait_with_output().unwrap()
+}
+
+fn stderr(output: &Output) -> String {
+    String::from_utf8_lossy(&output.stderr).into_owned()
+}
+
+#[test]
+fn test_downloads_input_puzzle_and_template() {
+    let (url, requests) = serve(site);
+    let dir = workdir("success");
+    fs::create_dir_all(dir.join("src")).unwrap();
+
+    let output = prepare(&dir, &url, SESSION, &["3"], "");
+    assert!(output.status.success(), "{}", stderr(&output));
+
+    assert_eq!(fs::read_to_string(dir.join("inputs/day03.txt")).unwrap(), "1\n2\n");
+    let puzzle = fs::read_to_string(dir.join("puzzles/day03.md")).unwrap();
+    assert!(puzzle.starts_with("## --- Day 3: Test ---"));
+    let template = fs::read_to_string(dir.join("src/day03.rs")).unwrap();
+    assert!(template.contains("pub struct Day03;"));
+    assert!(template.contains(r#".to_string(), "3");"#));
+
+    let requests = requests.lock().unwrap();
+    assert_eq!(requests.len(), 2);
+    assert!(requests.iter().all(|r| r.method == "GET"));
+}
+
+/// Like `site`, but the example answer is 0, which the template's placeholder
+/// parts return, so the generated tests pass
+fn solved_site(request: &Request) -> (u16, String) {
+    match request.path.as_str() {
+        "/2018/day/3" => (200, PUZZLE.replace("<em>3</em>", "<em>0</em>")),
+        _ => site(request),
+    }
+}
+
+#[test]
+fn test_rendered_template_matches_fixture() {
+    let (url, _) = serve(solved_site);
+    let dir = workdir("template-fixture");
+    fs::create_dir_all(dir.join("src")).unwrap();
+    let output = prepare(&dir, &url, SESSION, &["3"], "");
+    assert!(output.status.success(), "{}", stderr(&output));
+
+    // The fixture is compiled and tested with the library, so a template that
+    // doesn't build cleanly fails there; this keeps it in step with the template
+    let rendered = fs::read_to_string(dir.join("src/day03.rs")).unwrap();
+    assert!(
+        rendered == include_str!("fixtures/day03.rs"),
+        "templates/day.rs changed, update tests/fixtures/day03.rs to:\n{}",
+        rendered
+    );
+}
+
+#[test]
+fn test_custom_template() {
+    let (url, _) = serve(site);
+    let dir = workdir("template");
+    fs::create_dir_all(dir.join("src")).unwrap();
+    fs::create_dir_all(dir.join("templates")).unwrap();
+    let template = "// {{year}} day {{day}}\npub struct Day{{day_padded}};\n\n{{examples}}\n";
+    fs::write(dir.join("templates/day.rs"), template).unwrap();
+
+    let output = prepare(&dir, &url, SESSION, &["3"], "");
+    assert!(output.status.success(), "{}", stderr(&output));
+    let generated = fs::read_to_string(dir.join("src/day03.rs")).unwrap();
+    assert!(generated.starts_with("// 2018 day 3\npub struct Day03;\n\n    #[test]\n    fn test_part1() {"));
+
+    fs::remove_file(dir.join("src/day03.rs")).unwrap();
+    fs::write(dir.join("templates/day.rs"), "pub struct Day{{dya}};\n").unwrap();
+    let output = prepare(&dir, &url, SESSION, &["3"], "");
+    assert!(!output.status.success());
+    assert!(stderr(&output).contains("Unknown placeholder {{dya}}"));
+    assert!(!dir.join("src/day03.rs").exists());
+}
+
+#[test]
+fn test_registration_removes_stale_bin_targets() {
+    let (url, _) = serve(site);
+    let dir = workdir("register");
+    fs::create_dir_all(dir.join("src")).unwrap();
+    fs::write(dir.join("build.rs"), "fn main() {}\n").unwrap();
+    let cargo_toml = "[package]\nname = \"aoc\"\n\n[[bin]]\nname = \"day03\"\npath = \"src/day03.rs\"\n\n[dependencies]\n";
+    fs::write(dir.join("Cargo.toml"), cargo_toml).unwrap();
+
+    let output = prepare(&dir, &url, SESSION, &["3"], "");
+    assert!(output.status.success(), "{}", stderr(&output));
+    assert!(String::from_utf8_lossy(&output.stdout).contains("Removed [[bin]] day03 from Cargo.toml"));
+    let updated = fs::read_to_string(dir.join("Cargo.toml")).unwrap();
+    assert_eq!(updated, "[package]\nname = \"aoc\"\n\n[dependencies]\n");
+
+    let output = prepare(&dir, &url, SESSION, &["3"], "");
+    assert!(String::from_utf8_lossy(&output.stdout).contains("Cargo.toml needs no changes"));
+    assert_eq!(fs::read_to_string(dir.join("Cargo.toml")).unwrap(), updated);
+}
+
+#[test]
+fn test_bad_session_cookie() {
+    let (url, _) = serve(site);
+    let dir = workdir("bad-cookie");
+
+    let output = prepare(&dir, &url, "expired", &["3"], "");
+    assert!(!output.status.success());
+    assert!(stderr(&output).contains("HTTP 400"));
+    assert!(stderr(&output).contains("AOC_SESSION"));
+    assert!(!dir.join("inputs/day03.txt").exists());
+}
+
+#[test]
+fn test_session_from_dotenv_and_user_config() {
+    let (url, requests) = serve(site);
+    let dir = workdir("config");
+    fs::create_dir_all(dir.join("src")).unwrap();
+    fs::create_dir_all(dir.join("config/aoc2018")).unwrap();
+    fs::write(
+        dir.join("config/aoc2018/config"),
+        format!("AOC_SESSION={}\nAOC_INPUT_DIR=data\nAOC_USER_AGENT=tests\n", SESSION),
+    )
+    .unwrap();
+
+    let output = prepare(&dir, &url, "", &["3"], "");
+    assert!(output.status.success(), "{}", stderr(&output));
+    assert!(dir.join("data/day03.txt").exists());
+
+    // .env takes priority over the user config, and flags over both
+    fs::write(dir.join(".env"), "AOC_SESSION=expired\n").unwrap();
+    let output = prepare(&dir, &url, "", &["--force", "3"], "");
+    assert!(stderr(&output).contains("HTTP 400"));
+    let output = prepare(&dir, &url, "", &["--session", SESSION, "--input-dir", "other", "3"], "");
+    assert!(output.status.success(), "{}", stderr(&output));
+    assert!(dir.join("other/day03.txt").exists());
+
+    let requests = requests.lock().unwrap();
+    assert!(requests.iter().all(|r| r.user_agent.as_deref() == Some("tests")));
+}
+
+#[test]
+fn test_missing_session_lists_sources() {
+    let (url, requests) = serve(site);
+    let dir = workdir("no-session");
+
+    let output = prepare(&dir, &url, "", &["3"], "");
+    assert!(!output.status.success());
+    let message = stderr(&output);
+    assert!(message.contains("--session flag"));
+    assert!(message.contains("AOC_SESSION environment variable"));
+    assert!(message.contains(".env (not found)"));
+    assert!(message.contains("aoc2018/config (not found)"));
+    assert!(requests.lock().unwrap().is_empty());
+}
+
+#[test]
+fn test_year_setting() {
+    let (url, requests) = serve(site);
+    let dir = workdir("year");
+
+    let output = prepare(&dir, &url, SESSION, &["--year", "2017", "3"], "");
+    assert!(stderr(&output).contains("HTTP 404"));
+    assert_eq!(requests.lock().unwrap()[0].path, "/2017/day/3/input");
+}
+
+#[test]
+fn test_missing_day_is_404() {
+    let (url, _) = serve(site);
+    let dir = workdir("not-found");
+
+    let output = prepare(&dir, &url, SESSION, &["4"], "");
+    assert!(!output.status.success());
+    assert!(stderr(&output).contains("HTTP 404"));
+    assert!(!dir.join("inputs/day04.txt").exists());
+}
+
+#[test]
+fn test_server_error() {
+    let (url, _) = serve(|_| (500, "Internal Server Error".to_string()));
+    let dir = workdir("server-error");
+
+    let output = prepare(&dir, &url, SESSION, &["3"], "");
+    assert!(!output.status.success());
+    assert!(stderr(&output).contains("HTTP 500"));
+    assert!(!dir.join("inputs").join("day03.txt").exists());
+}
+
+#[test]
+fn test_existing_input_is_kept_unless_forced() {
+    let (url, _) = serve(site);
+    let dir = workdir("existing");
+    fs::create_dir_all(dir.join("inputs")).unwrap();
+    fs::create_dir_all(dir.join("src")).unwrap();
+    fs::write(dir.join("inputs/day03.txt"), "old\n").unwrap();
+    fs::write(dir.join("src/day03.rs"), "// solved\n").unwrap();
+
+    // Without a terminal there is no prompt, even if stdin says yes
+    let output = prepare(&dir, &url, SESSION, &["3"], "y\n");
+    assert!(output.status.success(), "{}", stderr(&output));
+    assert_eq!(fs::read_to_string(dir.join("inputs/day03.txt")).unwrap(), "old\n");
+
+    let output = prepare(&dir, &url, SESSION, &["--no-overwrite", "3"], "");
+    assert!(output.status.success(), "{}", stderr(&output));
+    assert_eq!(fs::read_to_string(dir.join("inputs/day03.txt")).unwrap(), "old\n");
+
+    let output = prepare(&dir, &url, SESSION, &["--force", "3"], "");
+    assert!(output.status.success(), "{}", stderr(&output));
+    assert_eq!(fs::read_to_string(dir.join("inputs/day03.txt")).unwrap(), "1\n2\n");
+    assert_eq!(fs::read_to_string(dir.join("src/day03.rs")).unwrap(), "// solved\n");
+}
+
+#[test]
+fn test_cached_input_is_not_downloaded_again() {
+    let (url, requests) = serve(site);
+    let dir = workdir("cache");
+    fs::create_dir_all(dir.join("src")).unwrap();
+
+    let output = prepare(&dir, &url, SESSION, &["3"], "");
+    assert!(output.status.success(), "{}", stderr(&output));
+    let manifest = fs::read_to_string(dir.join("inputs/manifest.tsv")).unwrap();
+    assert!(manifest.contains("2018/day03.txt\t"));
+
+    let output = prepare(&dir, &url, SESSION, &["3"], "");
+    assert!(String::from_utf8_lossy(&output.stdout).contains("cached and intact"));
+    let input_requests = |requests: &[Request]| requests.iter().filter(|r| r.path.ends_with("/input")).count();
+    assert_eq!(input_requests(&requests.lock().unwrap()), 1);
+
+    // A truncated file is reported and replaced with --force
+    fs::write(dir.join("inputs/day03.txt"), "1\n").unwrap();
+    let output = prepare(&dir, &url, SESSION, &["3"], "");
+    assert!(String::from_utf8_lossy(&output.stdout).contains("has changed since it was downloaded"));
+    let output = prepare(&dir, &url, SESSION, &["--force", "3"], "");
+    assert!(output.status.success(), "{}", stderr(&output));
+    assert_eq!(fs::read_to_string(dir.join("inputs/day03.txt")).unwrap(), "1\n2\n");
+    assert_eq!(input_requests(&requests.lock().unwrap()), 2);
+}
+
+#[test]
+fn test_error_page_is_not_saved_as_input() {
+    let (url, _) = serve(|_| (200, "<!DOCTYPE html>\n<html><body>Log in</body></html>\n".to_string()));
+    let dir = workdir("html");
+
+    let output = prepare(&dir, &url, SESSION, &["3"], "");
+    assert!(!output.status.success());
+    assert!(stderr(&output).contains("HTML page"));
+    assert!(!dir.join("inputs/day03.txt").exists());
+}
+
+#[test]
+fn test_requests_are_throttled() {
+    let (url, requests) = serve(site);
+    let dir = workdir("throttle");
+    fs::create_dir_all(dir.join("src")).unwrap();
+
+    let start = std::time::Instant::now();
+    let output = prepare(&dir, &url, SESSION, &["--request-interval", "0.5", "3"], "");
+    assert!(output.status.success(), "{}", stderr(&output));
+    assert_eq!(requests.lock().unwrap().len(), 2);
+    assert!(start.elapsed() >= std::time::Duration::from_millis(500));
+}
+
+#[test]
+fn test_conflicting_overwrite_flags() {
+    let (url, requests) = serve(site);
+    let dir = workdir("conflict");
+
+    let output = prepare(&dir, &url, SESSION, &["--force", "--no-overwrite", "3"], "");
+    assert!(!output.status.success());
+    assert!(stderr(&output).contains("cannot be combined"));
+    assert!(requests.lock().unwrap().is_empty());
+}
+
+#[test]
+fn test_dry_run_makes_no_requests_or_files() {
+    let (url, requests) = serve(site);
+    let dir = workdir("dry-run");
+    fs::create_dir_all(dir.join("src")).unwrap();
+
+    let output = prepare(&dir, &url, "", &["--dry-run", "--year", "2017", "3..4"], "");
+    assert!(output.status.success(), "{}", stderr(&output));
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains(&format!("Would download {}/2017/day/4/input", url)));
+    assert!(stdout.contains("Would create template file src/day03.rs"));
+
+    assert!(requests.lock().unwrap().is_empty());
+    assert!(!dir.join("inputs").exists());
+    assert!(!dir.join("src/day03.rs").exists());
+}
+
+#[test]
+fn test_day_range() {
+    let (url, requests) = serve(|request| match request.path.as_str() {
+        "/2018/day/1/input" | "/2018/day/2/input" => (200, "1\n".to_string()),
+        _ => (200, "<p>Not a puzzle page</p>".to_string()),
+    });
+    let dir = workdir("range");
+    fs::create_dir_all(dir.join("src")).unwrap();
+
+    let output = prepare(&dir, &url, SESSION, &["1..2"], "");
+    assert!(output.status.success(), "{}", stderr(&output));
+    assert!(dir.join("inputs/day01.txt").exists());
+    assert!(dir.join("inputs/day02.txt").exists());
+    assert!(dir.join("src/day02.rs").exists());
+    assert_eq!(requests.lock().unwrap().len(), 4);
+
+    let output = prepare(&dir, &url, SESSION, &["24..26"], "");
+    assert!(!output.status.success());
+    assert!(stderr(&output).contains("days are 1 to 25"));
+}
+
+#[test]
+fn test_submit_posts_answer_and_logs_outcome() {
+    let (url, requests) = serve(|request| match request.body.as_str() {
+        "level=1&answer=41" => (200, "<article><p>That's not the right answer; your answer is too low.</p></article>".to_string()),
+        _ => (200, "<article><p>That's the right answer!</p></article>".to_string()),
+    });
+    let dir = workdir("submit");
+
+    let output = prepare(&dir, &url, SESSION, &["submit", "3", "1", "41"], "");
+    assert!(!output.status.success());
+    let output = prepare(&dir, &url, SESSION, &["submit", "3", "1", "40"], "");
+    assert!(stderr(&output).contains("Not submitting"));
+    let output = prepare(&dir, &url, SESSION, &["submit", "3", "1", "42"], "");
+    assert!(output.status.success(), "{}", stderr(&output));
+
+
+    // Another year has its own log and answers, and no registered solutions
+    let output = prepare(&dir, &url, SESSION, &["--year", "2017", "submit", "3", "1"], "");
+    assert!(!output.status.success());
+    assert!(stderr(&output).contains("pass the answer explicitly"));
+    let output = prepare(&dir, &url, SESSION, &["--year", "2017", "submit", "3", "1", "42"], "");
+    assert!(output.status.success(), "{}", stderr(&output));
+
+    let requests = requests.lock().unwrap();
+    assert_eq!(requests.len(), 3);
+    assert_eq!(requests[0].method, "POST");
+    assert_eq!(requests[0].path, "/2018/day/3/answer");
+    assert_eq!(requests[2].path, "/2017/day/3/answer");
+
+    let log = fs::read_to_string(dir.join("answers/2018/day03.log")).unwrap();
+    assert_eq!(log.lines().count(), 2);
+    assert_eq!(fs::read_to_string(dir.join("answers/2018/day03.txt")).unwrap(), "part1: 42\n");
+    assert_eq!(fs::read_to_string(dir.join("answers/2017/day03.txt")).unwrap(), "part1: 42\n");
+}